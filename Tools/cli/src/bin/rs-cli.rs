@@ -1,12 +1,47 @@
-use std::{collections::HashSet, fs, path::PathBuf, process::exit};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+    process::exit,
+    time::{Duration, SystemTime},
+};
 
 use anyhow::{Context, Result};
 use clap::{Args, Parser, Subcommand, ValueEnum};
 use indicatif::ProgressBar;
 use itertools::Itertools;
 use log::LevelFilter;
+use rayon::prelude::*;
 use regex::Regex;
-use rs_cli::core::{item_definition::ItemDefinition, log::initialize_logging};
+use rs_cli::core::{
+    accounts::{self, RehashOutcome},
+    anonymize::{self, Pseudonyms},
+    bulk::{self, Action, JournalEntry, Selector},
+    cache::{IndexEntry, IndexedFileSystem, IntegrityError},
+    characters::{self, CharacterSave, ItemSlot, Position, Property},
+    collision::{self, CollisionMap, Placement, PlacementKind},
+    doors, drop_simulator,
+    economy::{self, Valuation},
+    equipment::{self, EquipmentDetails, EquipmentEntry},
+    floor_decoder, global_drops, highscores, item_decoder,
+    item_definition::{self, ItemDefinition},
+    item_migration::{self, FileMigration, ItemMapping},
+    item_stats::{self, Bonuses},
+    log::initialize_logging,
+    map,
+    map_render::{self, MapRenderer, Marker},
+    moderation::{self, ListFile, ModerationList, Rule},
+    modify::{self, modify, Modification, PendingChange, ACTION_SLOTS},
+    npc_data,
+    npc_decoder::{self, CacheNpcDefinition},
+    npc_definition::{self, NpcDefinition},
+    npc_drops, npc_migration,
+    object_decoder::{self, ObjectDefinition},
+    save_check, shops,
+    spawns::{self, Area, NpcSpawn},
+    validate::{self, DataPaths, Severity},
+};
+use serde::Serialize;
 
 #[derive(Parser)]
 #[clap(author, version, about, long_about = None)]
@@ -22,17 +57,730 @@ struct Cli {
 #[derive(Subcommand)]
 enum Commands {
     /// prints items found via the specified arguments
+    /// with only stat bounds, prints every item within the bounds
+    #[command(verbatim_doc_comment)]
     PrintItems {
         #[arg(short = 'f', long, default_value = "basic")]
         format: PrintFormat,
+        /// print the weight and equipment bonuses of each item
+        #[arg(long)]
+        bonuses: bool,
         #[command(flatten)]
         find_items: FindItems,
+        #[command(flatten)]
+        stat_bounds: StatBounds,
+    },
+    /// modifies the fields of items found via the specified arguments
+    ModifyItems {
+        /// print a unified diff of each changed file instead of writing it
+        #[arg(long)]
+        dry_run: bool,
+        #[command(flatten)]
+        find_items: FindItems,
+        #[command(flatten)]
+        edits: ItemEdits,
+    },
+    /// prints npc drop tables found via the specified npc and item arguments
+    /// with only item arguments, prints every npc that drops one of the items
+    #[command(verbatim_doc_comment)]
+    PrintDrops {
+        #[arg(short = 'f', long, default_value = "basic")]
+        format: PrintFormat,
+        /// the path to the npc drop tables
+        #[arg(long, default_value = "./data/cfg/npcdrops.json")]
+        drops_path: PathBuf,
+        #[command(flatten)]
+        find_npcs: FindNpcs,
+        #[command(flatten)]
+        find_items: FindItems,
+    },
+    /// simulates killing an npc using the server's drop rolling logic
+    SimulateDrops {
+        #[arg(short = 'f', long, default_value = "basic")]
+        format: ReportFormat,
+        /// the id of the npc to kill
+        #[arg(long)]
+        npc: i32,
+        /// the number of kills to simulate
+        #[arg(short = 'k', long, default_value = "100000")]
+        kills: u64,
+        /// seeds the random number generator to make the simulation reproducible
+        #[arg(long)]
+        seed: Option<u64>,
+        /// the path to the npc drop tables
+        #[arg(long, default_value = "./data/cfg/npcdrops.json")]
+        drops_path: PathBuf,
+        /// the path to the npc list
+        #[arg(long, default_value = "./data/cfg/npc.json")]
+        npc_list_path: PathBuf,
+        /// the directory containing item definitions
+        #[arg(short = 'p', long, default_value = "./data/item_definitions")]
+        items_path: PathBuf,
+    },
+    /// prints shops with their stock and prices
+    /// with item arguments, only prints shops which stock one of the items
+    #[command(verbatim_doc_comment)]
+    PrintShops {
+        #[arg(short = 'f', long, default_value = "basic")]
+        format: PrintFormat,
+        #[command(flatten)]
+        find_shops: FindShops,
+        #[command(flatten)]
+        find_items: FindItems,
+    },
+    /// checks shops for unknown, noted and duplicate items and arbitrage between shops
+    AuditShops {
+        #[arg(short = 'f', long, default_value = "basic")]
+        format: ReportFormat,
+        /// the path to the shop definitions
+        #[arg(long, default_value = "./data/cfg/shops.json")]
+        shops_path: PathBuf,
+        /// the directory containing item definitions
+        #[arg(short = 'p', long, default_value = "./data/item_definitions")]
+        items_path: PathBuf,
+    },
+    /// prints npc definitions found via the specified arguments
+    PrintNpcs {
+        #[arg(short = 'f', long, default_value = "basic")]
+        format: PrintFormat,
+        /// print the matching definitions in the layout of npcDefinitions.xml instead
+        #[arg(long, conflicts_with = "format")]
+        xml: bool,
+        /// where to read the npc definitions from
+        #[arg(long, default_value = "definitions")]
+        source: NpcSource,
+        /// the directory containing main_file_cache.dat and its index files, used with --source cache
+        #[arg(long, default_value = "./data/cache")]
+        cache_dir: PathBuf,
+        #[command(flatten)]
+        find_npc_definitions: FindNpcDefinitions,
+    },
+    /// prints object definitions from the cache found via the specified arguments
+    PrintObjects {
+        #[arg(short = 'f', long, default_value = "basic")]
+        format: PrintFormat,
+        #[command(flatten)]
+        find_objects: FindObjects,
+    },
+    /// prints npc spawns found via the specified npc and location arguments
+    PrintSpawns {
+        #[arg(short = 'f', long, default_value = "basic")]
+        format: PrintFormat,
+        /// the path to the npc spawns
+        #[arg(long, default_value = "./data/cfg/spawns.json")]
+        spawns_path: PathBuf,
+        #[command(flatten)]
+        find_npcs: FindNpcs,
+        #[command(flatten)]
+        find_spawns: FindSpawns,
+        /// print the number of matching spawns per region instead of the spawns
+        #[arg(long)]
+        density: bool,
+    },
+    /// prints the equipment slots of items found via the specified arguments
+    /// with only slot arguments, prints every item equipped in one of the slots
+    #[command(verbatim_doc_comment)]
+    PrintEquipment {
+        #[arg(short = 'f', long, default_value = "basic")]
+        format: PrintFormat,
+        #[command(flatten)]
+        equipment_file: EquipmentFile,
+        /// only match items equipped in the slot, given as a name such as legs or a number
+        /// can be specified multiple times
+        #[arg(long, value_parser = equipment::parse_slot, num_args(0..), verbatim_doc_comment)]
+        slot: Vec<i32>,
+        #[command(flatten)]
+        find_items: FindItems,
+    },
+    /// exports every entry of the equipment file to JSON
+    ExportEquipment {
+        #[command(flatten)]
+        equipment_file: EquipmentFile,
+        /// the path of the JSON file to write
+        json_path: PathBuf,
+    },
+    /// replaces the equipment file with the entries of a JSON file written by export-equipment
+    ImportEquipment {
+        #[command(flatten)]
+        equipment_file: EquipmentFile,
+        /// the path of the JSON file to read
+        json_path: PathBuf,
+    },
+    /// finds a walking path between two tiles using the clipping of the cache's maps
+    Path {
+        #[arg(short = 'f', long, default_value = "basic")]
+        format: ReportFormat,
+        /// the directory containing main_file_cache.dat and its index files
+        #[arg(long, default_value = "./data/cache")]
+        cache_dir: PathBuf,
+        /// the tile to start at as x,y,height
+        #[arg(long, value_parser = parse_tile)]
+        from: (i32, i32, i32),
+        /// the tile to walk to as x,y,height
+        #[arg(long, value_parser = parse_tile)]
+        to: (i32, i32, i32),
+        /// the most steps to search before giving up
+        #[arg(long, default_value_t = 256)]
+        max_steps: usize,
+    },
+    /// reports npc spawns, global drops and doors placed on tiles which are blocked
+    /// in the clipping of the cache's maps or which have no map at all
+    /// exits with a non-zero status if any are found
+    #[command(verbatim_doc_comment)]
+    CheckSpawns {
+        #[arg(short = 'f', long, default_value = "basic")]
+        format: ReportFormat,
+        /// the directory containing main_file_cache.dat and its index files
+        #[arg(long, default_value = "./data/cache")]
+        cache_dir: PathBuf,
+        /// the path to the npc spawns
+        #[arg(long, default_value = "./data/cfg/spawns.json")]
+        spawns_path: PathBuf,
+        /// the path to the global drops
+        #[arg(long, default_value = "./data/cfg/globaldrops.json")]
+        drops_path: PathBuf,
+        /// the path to the doors
+        #[arg(long, default_value = "./data/doors.json")]
+        doors_path: PathBuf,
+    },
+    /// prints a player save from the characters directory
+    PrintCharacter {
+        #[arg(short = 'f', long, default_value = "basic")]
+        format: ReportFormat,
+        /// the directory containing the player saves
+        #[arg(long, default_value = "./data/characters")]
+        characters_dir: PathBuf,
+        /// the directory containing item definitions
+        #[arg(short = 'p', long, default_value = "./data/item_definitions")]
+        items_path: PathBuf,
+        /// the name of the save file without .txt
+        name: String,
+    },
+    /// edits a player save in the characters directory
+    /// the server overwrites the save when the player logs out, only edit offline players
+    #[command(verbatim_doc_comment)]
+    EditCharacter {
+        /// the directory containing the player saves
+        #[arg(long, default_value = "./data/characters")]
+        characters_dir: PathBuf,
+        /// the name of the save file without .txt
+        name: String,
+        /// sets the experience of a skill in the form SKILL=XP, e.g. mining=13363
+        /// the level is restored to the level for the experience, can be specified multiple times
+        #[arg(long, value_parser = parse_skill_xp, verbatim_doc_comment)]
+        set_xp: Vec<(usize, i32)>,
+        /// adds an item to the bank in the form ID=AMOUNT, e.g. 995=1000
+        /// notes are added as the item they represent, can be specified multiple times
+        #[arg(long, value_parser = parse_item_amount, verbatim_doc_comment)]
+        add_bank: Vec<(i32, i32)>,
+        /// the directory containing item definitions, which the added items are checked against
+        #[arg(short = 'p', long, default_value = "./data/item_definitions")]
+        items_path: PathBuf,
+        /// moves the player to the tile x,y,height
+        #[arg(long, value_parser = parse_position)]
+        position: Option<(i32, i32, i32)>,
+        /// print a unified diff of the save instead of writing it
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// reports the wealth held across every player save
+    /// items are valued at their definition value, notes at the value of the item they represent
+    #[command(verbatim_doc_comment)]
+    Economy {
+        #[arg(short = 'f', long, default_value = "basic")]
+        format: ReportFormat,
+        /// the directory containing the player saves
+        #[arg(long, default_value = "./data/characters")]
+        characters_dir: PathBuf,
+        /// the directory containing item definitions
+        #[arg(short = 'p', long, default_value = "./data/item_definitions")]
+        items_path: PathBuf,
+        /// the number of holders and items to print
+        #[arg(long, default_value_t = 10)]
+        top: usize,
+    },
+    /// replaces item ids in the character saves, npc drops, shops, global drops and item id lists
+    /// the noted counterparts of the items are replaced as well
+    /// nothing is written unless every file can be migrated
+    #[command(verbatim_doc_comment)]
+    MigrateItem {
+        /// the item id to replace, requires --to
+        #[arg(long, requires = "to")]
+        from: Option<i32>,
+        /// the item id to replace it with
+        #[arg(long, requires = "from")]
+        to: Option<i32>,
+        /// a JSON file mapping old item ids to new ones, e.g. {"1234": 5678}
+        #[arg(long)]
+        mapping_path: Option<PathBuf>,
+        /// the server's data directory
+        #[arg(short = 'd', long, default_value = "./data")]
+        data_dir: PathBuf,
+        /// the directory containing the item id lists
+        #[arg(long, default_value = "../Tools/cli/data/item_ids")]
+        item_ids_dir: PathBuf,
+        /// where to write the JSON report of every file and slot touched
+        #[arg(long, default_value = "./item_migration_report.json")]
+        report_path: PathBuf,
+        /// print a unified diff of each changed file instead of writing it
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// manages the ban, mute and host blacklist files
+    /// the server reads them at startup
+    #[command(verbatim_doc_comment)]
+    Moderation {
+        #[command(subcommand)]
+        command: ModerationCommand,
+    },
+    /// ranks the players in the player saves like HighscoresHandler and writes
+    /// highscores.json and index.html to the output directory
+    /// admins, developers, bots and names starting with ♥ are not ranked
+    #[command(verbatim_doc_comment)]
+    Highscores {
+        /// the directory containing the player saves
+        #[arg(long, default_value = "./data/characters")]
+        characters_dir: PathBuf,
+        /// the directory containing item definitions
+        #[arg(short = 'p', long, default_value = "./data/item_definitions")]
+        items_path: PathBuf,
+        /// the directory to write the highscores to
+        #[arg(short = 'o', long, default_value = "./highscores")]
+        output_dir: PathBuf,
+        /// the number of players to keep in each ranking
+        #[arg(long, default_value_t = 100)]
+        top: usize,
+    },
+    /// maintains the accounts in the player saves
+    Accounts {
+        #[command(subcommand)]
+        command: AccountsCommand,
+    },
+    /// reads files from the game cache
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommand,
+    },
+    /// converts server data between the legacy files and one JSON file per id
+    Migrate {
+        #[command(subcommand)]
+        migration: Migration,
+    },
+    /// checks the references between all server data files
+    /// exits with a non-zero status if any errors are found,
+    /// the known issues of the repository's data are reported as warnings
+    #[command(verbatim_doc_comment)]
+    Validate {
+        #[arg(short = 'f', long, default_value = "basic")]
+        format: ValidateFormat,
+        /// the server's data directory
+        #[arg(short = 'd', long, default_value = "./data")]
+        data_dir: PathBuf,
     },
     /// command for testing
     #[cfg(debug_assertions)]
     Debug,
 }
 
+#[derive(Args, Debug)]
+struct EquipmentFile {
+    /// the path to the equipment file
+    #[arg(long, default_value = "./data/data/equipment.dat")]
+    equipment_path: PathBuf,
+    /// the layout of the equipment file
+    #[arg(long, default_value = "slots")]
+    layout: EquipmentLayout,
+}
+
+impl EquipmentFile {
+    fn load(&self) -> Result<Vec<EquipmentEntry>> {
+        equipment::load(&self.equipment_path, self.layout.layout())
+    }
+}
+
+#[derive(Debug, Clone, ValueEnum)]
+enum EquipmentLayout {
+    /// one slot byte per item, read by the server from data/data/equipment.dat
+    Slots,
+    /// slots, flags and requirements written by apollo's EquipmentUpdater
+    Apollo,
+}
+
+impl EquipmentLayout {
+    fn layout(&self) -> equipment::Layout {
+        match self {
+            EquipmentLayout::Slots => equipment::Layout::Slots,
+            EquipmentLayout::Apollo => equipment::Layout::Apollo,
+        }
+    }
+}
+
+#[derive(Subcommand, Debug)]
+enum CacheCommand {
+    /// lists the files of each index with their size and first sector
+    /// every file is read to report broken sector chains
+    #[command(verbatim_doc_comment)]
+    List {
+        #[arg(short = 'f', long, default_value = "basic")]
+        format: ReportFormat,
+        #[command(flatten)]
+        find_files: FindCacheFiles,
+    },
+    /// reports every item whose name, actions, stackable and members flags, value or
+    /// note ids differ between the item definitions and the cache's config archive
+    #[command(verbatim_doc_comment)]
+    DiffItems {
+        #[arg(short = 'f', long, default_value = "basic")]
+        format: ReportFormat,
+        /// the directory containing main_file_cache.dat and its index files
+        #[arg(long, default_value = "./data/cache")]
+        cache_dir: PathBuf,
+        /// the directory containing item definitions
+        #[arg(short = 'p', long, default_value = "./data/item_definitions")]
+        items_path: PathBuf,
+    },
+    /// renders top down PNG images of regions or of an area from the cache's maps
+    /// regions are written to {output_dir}/{region}.png and areas to {output_dir}/{x1}_{y1}_{x2}_{y2}.png
+    #[command(verbatim_doc_comment)]
+    RenderMap {
+        /// the directory containing main_file_cache.dat and its index files
+        #[arg(long, default_value = "./data/cache")]
+        cache_dir: PathBuf,
+        /// the id of a region to render, as printed by print-spawns --density
+        /// can be specified multiple times
+        #[arg(long, num_args(0..), verbatim_doc_comment)]
+        region: Vec<i32>,
+        /// an area to render as x1,y1,x2,y2
+        /// can be specified multiple times
+        #[arg(long, num_args(0..), verbatim_doc_comment)]
+        area: Vec<Area>,
+        /// the plane to render
+        #[arg(long, default_value_t = 0)]
+        height: i32,
+        /// the size of a tile in pixels
+        #[arg(long, default_value_t = 4, value_parser = clap::value_parser!(u32).range(1..=32))]
+        scale: u32,
+        /// the directory to write the images to
+        #[arg(short = 'o', long, default_value = "./maps")]
+        output_dir: PathBuf,
+        #[command(flatten)]
+        map_overlays: MapOverlays,
+    },
+    /// writes the raw files of the cache to {output_dir}/{index}/{file}.dat
+    /// exits with a non-zero status if any file could not be read
+    #[command(verbatim_doc_comment)]
+    Extract {
+        #[command(flatten)]
+        find_files: FindCacheFiles,
+        /// the directory to write the files to
+        #[arg(short = 'o', long, default_value = "./cache-files")]
+        output_dir: PathBuf,
+    },
+}
+
+#[derive(Args, Debug)]
+struct MapOverlays {
+    /// draw the npc spawns in yellow
+    #[arg(long)]
+    spawns: bool,
+    /// the path to the npc spawns
+    #[arg(long, default_value = "./data/cfg/spawns.json")]
+    spawns_path: PathBuf,
+    /// draw the global drops in red
+    #[arg(long)]
+    drops: bool,
+    /// the path to the global drops
+    #[arg(long, default_value = "./data/cfg/globaldrops.json")]
+    drops_path: PathBuf,
+    /// draw the doors in cyan
+    #[arg(long)]
+    doors: bool,
+    /// the path to the doors
+    #[arg(long, default_value = "./data/doors.json")]
+    doors_path: PathBuf,
+}
+
+impl MapOverlays {
+    fn load_markers(&self) -> Result<Vec<Marker>> {
+        let mut markers = Vec::new();
+        if self.spawns {
+            markers.extend(
+                spawns::load_all(&self.spawns_path)?
+                    .iter()
+                    .map(|spawn| Marker {
+                        x: spawn.x,
+                        y: spawn.y,
+                        height: spawn.height,
+                        colour: map_render::NPC_COLOUR,
+                    }),
+            );
+        }
+        if self.drops {
+            markers.extend(
+                global_drops::load_all(&self.drops_path)?
+                    .iter()
+                    .map(|drop| Marker {
+                        x: drop.item_x,
+                        y: drop.item_y,
+                        height: drop.height(),
+                        colour: map_render::GROUND_ITEM_COLOUR,
+                    }),
+            );
+        }
+        if self.doors {
+            for door in doors::load_all(&self.doors_path)? {
+                markers.extend(door.locations.iter().map(|location| Marker {
+                    x: location.x,
+                    y: location.y,
+                    height: location.height,
+                    colour: map_render::DOOR_COLOUR,
+                }));
+            }
+        }
+        Ok(markers)
+    }
+}
+
+#[derive(Args, Debug)]
+struct FindCacheFiles {
+    /// the directory containing main_file_cache.dat and its index files
+    #[arg(long, default_value = "./data/cache")]
+    cache_dir: PathBuf,
+    /// the index to read files from, all indices are read if not specified
+    /// can be specified multiple times
+    #[arg(long, num_args(0..), verbatim_doc_comment)]
+    index: Vec<usize>,
+    /// the id of a file to read from each index, all files are read if not specified
+    /// can be specified multiple times
+    #[arg(long, num_args(0..), verbatim_doc_comment)]
+    file: Vec<usize>,
+}
+
+impl FindCacheFiles {
+    /// returns every matching (index, file) pair of the cache
+    fn select(&self, cache: &IndexedFileSystem) -> Result<Vec<(usize, usize)>> {
+        let indices = if self.index.is_empty() {
+            cache.indices()
+        } else {
+            self.index.clone()
+        };
+
+        let mut files = Vec::new();
+        for index in indices {
+            let count = cache.file_count(index)?;
+            if self.file.is_empty() {
+                files.extend((0..count).map(|file| (index, file)));
+            } else {
+                files.extend(self.file.iter().map(|file| (index, *file)));
+            }
+        }
+        Ok(files)
+    }
+}
+
+#[derive(Subcommand, Debug)]
+enum AccountsCommand {
+    /// replaces plaintext passwords with the hash PlayerSave.passwordHash produces,
+    /// which the server already accepts at login, and backs up every changed save
+    /// plaintext passwords are compared ignoring case at login, hashed passwords are not
+    #[command(verbatim_doc_comment)]
+    Rehash {
+        #[arg(short = 'f', long, default_value = "basic")]
+        format: ReportFormat,
+        /// the directory containing the player saves
+        #[arg(long, default_value = "./data/characters")]
+        characters_dir: PathBuf,
+        /// the directory the original saves are copied to, in a subdirectory per run
+        #[arg(long, default_value = "./data/character_backups")]
+        backup_dir: PathBuf,
+        /// report what would be changed without writing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// applies actions to every player save matching the selectors, every selector given
+    /// must match and a save is selected by default
+    /// the original saves and a journal of every change are written to the backup directory
+    #[command(verbatim_doc_comment)]
+    Bulk {
+        #[arg(short = 'f', long, default_value = "basic")]
+        format: ReportFormat,
+        /// the directory containing the player saves
+        #[arg(long, default_value = "./data/characters")]
+        characters_dir: PathBuf,
+        /// selects saves whose file name without .txt matches the regex
+        #[arg(long, value_parser = Regex::new)]
+        name: Option<Regex>,
+        /// selects saves with the rights
+        #[arg(long)]
+        rights: Option<i32>,
+        /// selects bots with true and players with false
+        #[arg(long)]
+        bot: Option<bool>,
+        /// selects saves positioned in the area x1,y1,x2,y2 on any height
+        #[arg(long)]
+        area: Option<Area>,
+        /// selects saves holding the item id in the inventory, equipment or bank
+        #[arg(long)]
+        owns: Option<i32>,
+        /// adds an item to the bank in the form ID=AMOUNT, e.g. 995=1000
+        /// notes are added as the item they represent, can be specified multiple times
+        #[arg(long, value_parser = parse_item_amount, verbatim_doc_comment)]
+        give: Vec<(i32, i32)>,
+        /// the directory containing item definitions, which the given items are checked against
+        #[arg(short = 'p', long, default_value = "./data/item_definitions")]
+        items_path: PathBuf,
+        /// moves the players to respawn_x and respawn_y of the server config
+        #[arg(long)]
+        reset_position: bool,
+        /// removes a [CHARACTER] key such as a quest stage, which the server then reads as 0
+        /// can be specified multiple times
+        #[arg(long, verbatim_doc_comment)]
+        clear: Vec<String>,
+        /// the server config holding the respawn, the server's defaults are used if it is missing
+        #[arg(long, default_value = "./ServerConfig.json")]
+        config_path: PathBuf,
+        /// the directory the original saves and the journal are written to,
+        /// in a subdirectory per run
+        #[arg(long, default_value = "./data/character_backups", verbatim_doc_comment)]
+        backup_dir: PathBuf,
+        /// report what would be changed without writing anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// writes copies of player saves that can be shared, with the username, friends and
+    /// ignores replaced by pseudonyms, the password removed so any password logs in,
+    /// the last and creation ip replaced by 127.0.0.1, and the bank pin and discord user
+    /// cleared, the copies are named after their pseudonym
+    #[command(verbatim_doc_comment)]
+    Anonymize {
+        /// the directory containing the player saves
+        #[arg(long, default_value = "./data/characters")]
+        characters_dir: PathBuf,
+        /// the directory to write the anonymized saves to
+        #[arg(short = 'o', long, default_value = "./anonymized")]
+        output_dir: PathBuf,
+        /// the names mapped to their pseudonyms, which is extended with every new name
+        /// so a name gets the same pseudonym in every save, keep it private
+        #[arg(long, default_value = "./data/pseudonyms.json", verbatim_doc_comment)]
+        pseudonyms_path: PathBuf,
+        /// the names of the save files without .txt
+        #[arg(required = true)]
+        names: Vec<String>,
+    },
+    /// checks the player saves against the item definitions and the experience table,
+    /// printing every problem with its file and line
+    /// exits with a non-zero status if any problems are left unrepaired
+    #[command(verbatim_doc_comment)]
+    Check {
+        #[arg(short = 'f', long, default_value = "basic")]
+        format: ReportFormat,
+        /// the directory containing the player saves
+        #[arg(long, default_value = "./data/characters")]
+        characters_dir: PathBuf,
+        /// the directory containing item definitions
+        #[arg(short = 'p', long, default_value = "./data/item_definitions")]
+        items_path: PathBuf,
+        /// fixes the safe cases by dropping invalid slots, clamping amounts and experience,
+        /// separating values with tabs and recomputing levels no boost or drain reaches
+        /// from experience
+        #[arg(long, verbatim_doc_comment)]
+        repair: bool,
+        /// the directory the original saves are copied to before repairing them,
+        /// in a subdirectory per run
+        #[arg(long, default_value = "./data/character_backups", verbatim_doc_comment)]
+        backup_dir: PathBuf,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum ModerationCommand {
+    /// prints the entries of the lists with the expiry and reason of entries added by rs-cli
+    List {
+        #[arg(short = 'f', long, default_value = "basic")]
+        format: ReportFormat,
+        /// the server's data directory
+        #[arg(short = 'd', long, default_value = "./data")]
+        data_dir: PathBuf,
+        /// the list to print, one of banned-users, muted-users, banned-ips, muted-ips
+        /// or blacklisted-hosts, prints every list if not specified
+        #[arg(value_parser = ModerationList::parse, verbatim_doc_comment)]
+        list: Option<ModerationList>,
+    },
+    /// adds entries to a list, the ip lists also accept CIDR ranges
+    /// which are expanded to every address since the server only matches exact addresses
+    #[command(verbatim_doc_comment)]
+    Add {
+        /// the server's data directory
+        #[arg(short = 'd', long, default_value = "./data")]
+        data_dir: PathBuf,
+        /// the list to add to, one of banned-users, muted-users, banned-ips, muted-ips
+        /// or blacklisted-hosts
+        #[arg(value_parser = ModerationList::parse, verbatim_doc_comment)]
+        list: ModerationList,
+        /// the names, ips, CIDR ranges or hosts to add
+        #[arg(required = true)]
+        entries: Vec<String>,
+        /// how long until the entries expire, e.g. 7d or 12h, never if not specified
+        #[arg(long, value_parser = humantime::parse_duration)]
+        duration: Option<Duration>,
+        /// why the entries were added
+        #[arg(long)]
+        reason: Option<String>,
+    },
+    /// removes entries and CIDR ranges from a list
+    /// an entry or range added by rs-cli only removes the entries it added to the list
+    #[command(verbatim_doc_comment)]
+    Remove {
+        /// the server's data directory
+        #[arg(short = 'd', long, default_value = "./data")]
+        data_dir: PathBuf,
+        /// the list to remove from, one of banned-users, muted-users, banned-ips, muted-ips
+        /// or blacklisted-hosts
+        #[arg(value_parser = ModerationList::parse, verbatim_doc_comment)]
+        list: ModerationList,
+        /// the names, ips, CIDR ranges or hosts to remove
+        #[arg(required = true)]
+        entries: Vec<String>,
+    },
+    /// removes the entries whose expiry has passed from every list
+    Expire {
+        /// the server's data directory
+        #[arg(short = 'd', long, default_value = "./data")]
+        data_dir: PathBuf,
+        /// print the entries which would be removed instead of removing them
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum Migration {
+    /// merges npcDefinitions.xml and npc.json into one JSON file per npc and reports
+    /// every field where they disagree, the values from both files are kept
+    /// with --regenerate, writes both legacy files from the JSON files instead, which gives
+    /// back the files as they were migrated, npcs added since are appended to npc.json
+    #[command(verbatim_doc_comment)]
+    Npcs {
+        #[arg(short = 'f', long, default_value = "basic")]
+        format: ReportFormat,
+        /// the path to the npc definitions
+        #[arg(long, default_value = "./data/cfg/npcDefinitions.xml")]
+        definitions_path: PathBuf,
+        /// the path to the npc list
+        #[arg(long, default_value = "./data/cfg/npc.json")]
+        npc_list_path: PathBuf,
+        /// the directory containing one JSON file per npc
+        #[arg(short = 'o', long, default_value = "./data/npc_definitions")]
+        output_dir: PathBuf,
+        /// the order and whitespace of the legacy files, kept apart from the npcs
+        #[arg(long, default_value = "./data/npc_definitions_layout.json")]
+        layout_path: PathBuf,
+        /// regenerate npcDefinitions.xml and npc.json from the output directory
+        #[arg(long)]
+        regenerate: bool,
+    },
+}
+
 #[derive(Args, Debug)]
 struct FindItems {
     /// the directory containing item definitions
@@ -50,16 +798,383 @@ struct FindItems {
     id_name_tuples_json: Vec<PathBuf>,
 }
 
+impl FindItems {
+    fn has_selectors(&self) -> bool {
+        !(self.regex_pattern.is_empty()
+            && self.ids_json.is_empty()
+            && self.id_name_tuples_json.is_empty())
+    }
+}
+
+#[derive(Args, Debug)]
+struct FindNpcDefinitions {
+    /// the path to the npc definitions
+    #[arg(long, default_value = "./data/cfg/npcDefinitions.xml")]
+    definitions_path: PathBuf,
+    /// the regular expression to match against npc names
+    /// can be specified multiple times to match against any of the given patterns
+    #[arg(short = 'r', long, visible_alias("pattern"), num_args(0..), verbatim_doc_comment)]
+    regex_pattern: Vec<String>,
+    /// the path to a JSON file containing an array of npc ids
+    #[arg(short = 'i', long, num_args(0..))]
+    ids_json: Vec<PathBuf>,
+    /// the path to a JSON file containing an array of npc ids and names as tuples
+    #[arg(short = 'n', long, num_args(0..))]
+    id_name_tuples_json: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone, ValueEnum)]
+enum NpcSource {
+    /// the server's npcDefinitions.xml
+    Definitions,
+    /// npc.dat in the config archive of the cache
+    Cache,
+}
+
+#[derive(Args, Debug)]
+struct FindObjects {
+    /// the directory containing main_file_cache.dat and its index files
+    #[arg(long, default_value = "./data/cache")]
+    cache_dir: PathBuf,
+    /// the regular expression to match against object names
+    /// can be specified multiple times to match against any of the given patterns
+    #[arg(short = 'r', long, visible_alias("pattern"), num_args(0..), verbatim_doc_comment)]
+    regex_pattern: Vec<String>,
+    /// the regular expression to match against object actions such as Open or Use-quickly
+    /// can be specified multiple times to match against any of the given patterns
+    #[arg(short = 'a', long, num_args(0..), verbatim_doc_comment)]
+    action_pattern: Vec<String>,
+    /// the path to a JSON file containing an array of object ids
+    #[arg(short = 'i', long, num_args(0..))]
+    ids_json: Vec<PathBuf>,
+    /// the path to a JSON file containing an array of object ids and names as tuples
+    #[arg(short = 'n', long, num_args(0..))]
+    id_name_tuples_json: Vec<PathBuf>,
+}
+
+impl FindObjects {
+    /// returns every object matching any of the selectors
+    fn select<'a>(&self, objects: &'a [ObjectDefinition]) -> Result<Vec<&'a ObjectDefinition>> {
+        let patterns = compile_patterns(&self.regex_pattern)?;
+        let action_patterns = compile_patterns(&self.action_pattern)?;
+        let desired_ids = read_ids(&self.ids_json, &self.id_name_tuples_json)?;
+
+        log::info!("searching objects...");
+        Ok(objects
+            .iter()
+            .filter(|object| {
+                let name = object.name.as_deref().unwrap_or("");
+                patterns.iter().any(|pattern| pattern.is_match(name))
+                    || object.menu_actions().any(|action| {
+                        action_patterns
+                            .iter()
+                            .any(|pattern| pattern.is_match(action))
+                    })
+                    || desired_ids.contains(&object.id)
+            })
+            .collect())
+    }
+}
+
+#[derive(Args, Debug)]
+struct FindNpcs {
+    /// the path to the npc list
+    #[arg(long, default_value = "./data/cfg/npc.json")]
+    npc_list_path: PathBuf,
+    /// the id of an npc to match
+    /// can be specified multiple times
+    #[arg(long, num_args(0..), verbatim_doc_comment)]
+    npc_id: Vec<i32>,
+    /// the regular expression to match against npc names
+    /// can be specified multiple times to match against any of the given patterns
+    #[arg(long, num_args(0..), verbatim_doc_comment)]
+    npc_pattern: Vec<String>,
+}
+
+impl FindNpcs {
+    fn has_selectors(&self) -> bool {
+        !(self.npc_id.is_empty() && self.npc_pattern.is_empty())
+    }
+
+    /// returns the ids of all npcs in the npc list matching the selectors
+    fn select(&self, names: &HashMap<i32, String>) -> Result<HashSet<i32>> {
+        let patterns = compile_patterns(&self.npc_pattern)?;
+        let mut ids: HashSet<i32> = self.npc_id.iter().copied().collect();
+        ids.extend(
+            names
+                .iter()
+                .filter(|(_, name)| patterns.iter().any(|pattern| pattern.is_match(name)))
+                .map(|(id, _)| *id),
+        );
+        Ok(ids)
+    }
+}
+
+#[derive(Args, Debug)]
+struct FindSpawns {
+    /// only match spawns inside the box in the form x1,y1,x2,y2
+    #[arg(long)]
+    area: Option<Area>,
+    /// only match spawns within --radius tiles of the coordinate in the form x,y
+    #[arg(long, value_parser = parse_coordinate)]
+    near: Option<(i32, i32)>,
+    /// the radius used by --near
+    #[arg(long, default_value = "10")]
+    radius: f64,
+    /// only match spawns on the height level
+    #[arg(long)]
+    height: Option<i32>,
+    /// only match spawns in the region, where the region id is (x >> 6) << 8 | (y >> 6)
+    /// can be specified multiple times
+    #[arg(long, num_args(0..), verbatim_doc_comment)]
+    region: Vec<i32>,
+}
+
+impl FindSpawns {
+    fn is_match(&self, spawn: &NpcSpawn) -> bool {
+        self.area.is_none_or(|area| area.contains(spawn.x, spawn.y))
+            && self
+                .near
+                .is_none_or(|(x, y)| spawn.distance_to(x, y) <= self.radius)
+            && self.height.is_none_or(|height| spawn.height == height)
+            && (self.region.is_empty() || self.region.contains(&spawn.region_id()))
+    }
+}
+
+/// a player position, heights above 3 are instances of the planes
+fn parse_position(s: &str) -> Result<(i32, i32, i32)> {
+    let coordinates = s
+        .split(',')
+        .map(|c| c.trim().parse::<i32>())
+        .collect::<Result<Vec<_>, _>>()
+        .with_context(|| format!("could not parse position {s}"))?;
+    match coordinates[..] {
+        [_, _, height] if height < 0 => {
+            anyhow::bail!("expected a height of at least 0 but got {height}")
+        }
+        [x, y, height] => Ok((x, y, height)),
+        _ => anyhow::bail!("expected a position in the form x,y,height but got {s}"),
+    }
+}
+
+/// a position on one of the planes of the map
+fn parse_tile(s: &str) -> Result<(i32, i32, i32)> {
+    let (x, y, height) = parse_position(s)?;
+    if !(0..map::MAP_PLANES).contains(&height) {
+        anyhow::bail!("expected a height between 0 and 3 but got {height}");
+    }
+    Ok((x, y, height))
+}
+
+fn parse_skill_xp(s: &str) -> Result<(usize, i32)> {
+    let (skill, xp) = s
+        .split_once('=')
+        .with_context(|| format!("expected SKILL=XP but got {s}"))?;
+    Ok((characters::parse_skill(skill.trim())?, xp.trim().parse()?))
+}
+
+fn parse_item_amount(s: &str) -> Result<(i32, i32)> {
+    let (id, amount) = s
+        .split_once('=')
+        .with_context(|| format!("expected ID=AMOUNT but got {s}"))?;
+    Ok((id.trim().parse()?, amount.trim().parse()?))
+}
+
+fn parse_coordinate(s: &str) -> Result<(i32, i32)> {
+    let (x, y) = s
+        .split_once(',')
+        .with_context(|| format!("expected a coordinate in the form x,y but got {s}"))?;
+    Ok((x.trim().parse()?, y.trim().parse()?))
+}
+
+#[derive(Args, Debug)]
+struct FindShops {
+    /// the path to the shop definitions
+    #[arg(long, default_value = "./data/cfg/shops.json")]
+    shops_path: PathBuf,
+    /// the id of a shop to match
+    /// can be specified multiple times
+    #[arg(long, num_args(0..), verbatim_doc_comment)]
+    shop_id: Vec<i32>,
+    /// the regular expression to match against shop names
+    /// can be specified multiple times to match against any of the given patterns
+    #[arg(long, num_args(0..), verbatim_doc_comment)]
+    shop_pattern: Vec<String>,
+}
+
+impl FindShops {
+    fn has_selectors(&self) -> bool {
+        !(self.shop_id.is_empty() && self.shop_pattern.is_empty())
+    }
+}
+
+#[derive(Args, Debug)]
+struct ItemEdits {
+    /// the new item name, an empty string removes the name
+    #[arg(long)]
+    name: Option<String>,
+    /// the new item description, an empty string removes the description
+    #[arg(long)]
+    description: Option<String>,
+    #[arg(long)]
+    value: Option<i32>,
+    #[arg(long)]
+    members: Option<bool>,
+    #[arg(long)]
+    stackable: Option<bool>,
+    #[arg(long)]
+    team: Option<i32>,
+    /// the new note graphic id, -1 removes it
+    #[arg(long, allow_negative_numbers = true)]
+    note_graphic_id: Option<i32>,
+    /// the new note info id, -1 removes it
+    #[arg(long, allow_negative_numbers = true)]
+    note_info_id: Option<i32>,
+    /// sets a ground action slot in the form SLOT=ACTION, e.g. 2=Take
+    /// an empty action clears the slot, can be specified multiple times
+    #[arg(long, value_parser = parse_action_slot, verbatim_doc_comment)]
+    ground_action: Vec<(usize, Option<String>)>,
+    /// sets an inventory action slot in the form SLOT=ACTION, e.g. 1=Wield
+    /// an empty action clears the slot, can be specified multiple times
+    #[arg(long, value_parser = parse_action_slot, verbatim_doc_comment)]
+    inventory_action: Vec<(usize, Option<String>)>,
+}
+
+impl ItemEdits {
+    fn modifications(&self) -> Vec<Modification> {
+        let text = |s: &String| (!s.is_empty()).then(|| s.clone());
+        let id = |i: i32| (i != -1).then_some(i);
+
+        let mut modifications = Vec::new();
+        modifications.extend(self.name.as_ref().map(|n| Modification::Name(text(n))));
+        modifications.extend(
+            self.description
+                .as_ref()
+                .map(|d| Modification::Description(text(d))),
+        );
+        modifications.extend(self.value.map(Modification::Value));
+        modifications.extend(self.members.map(Modification::Members));
+        modifications.extend(self.stackable.map(Modification::Stackable));
+        modifications.extend(self.team.map(Modification::Team));
+        modifications.extend(
+            self.note_graphic_id
+                .map(|i| Modification::NoteGraphicId(id(i))),
+        );
+        modifications.extend(self.note_info_id.map(|i| Modification::NoteInfoId(id(i))));
+        modifications.extend(self.ground_action.iter().map(|(slot, action)| {
+            Modification::GroundAction {
+                slot: *slot,
+                action: action.clone(),
+            }
+        }));
+        modifications.extend(self.inventory_action.iter().map(|(slot, action)| {
+            Modification::InventoryAction {
+                slot: *slot,
+                action: action.clone(),
+            }
+        }));
+        modifications
+    }
+}
+
+fn parse_action_slot(s: &str) -> Result<(usize, Option<String>)> {
+    let (slot, action) = s
+        .split_once('=')
+        .with_context(|| format!("expected SLOT=ACTION but got {s}"))?;
+    let slot: usize = slot.trim().parse()?;
+    if slot >= ACTION_SLOTS {
+        anyhow::bail!("action slot {slot} is out of range, expected 0..{ACTION_SLOTS}");
+    }
+    Ok((slot, (!action.is_empty()).then(|| action.to_string())))
+}
+
+#[derive(Args, Debug)]
+struct StatBounds {
+    /// the path to the item weights and equipment bonuses
+    #[arg(long, default_value = "./data/cfg/ItemDefinitions.json")]
+    stats_path: PathBuf,
+    /// only match items with at least the value for the stat in the form STAT=VALUE
+    /// the stat is weight or a bonus as named in ItemDefinitions.json, e.g. strengthBonus=10
+    /// can be specified multiple times
+    #[arg(long, value_parser = parse_stat_bound, num_args(0..), verbatim_doc_comment)]
+    min: Vec<(String, f64)>,
+    /// only match items with at most the value for the stat in the form STAT=VALUE
+    /// can be specified multiple times
+    #[arg(long, value_parser = parse_stat_bound, num_args(0..), verbatim_doc_comment)]
+    max: Vec<(String, f64)>,
+}
+
+impl StatBounds {
+    fn has_bounds(&self) -> bool {
+        !(self.min.is_empty() && self.max.is_empty())
+    }
+
+    fn is_match(&self, item: &ItemDefinition) -> bool {
+        self.min
+            .iter()
+            .all(|(stat, min)| item_stat(item, stat) >= *min)
+            && self
+                .max
+                .iter()
+                .all(|(stat, max)| item_stat(item, stat) <= *max)
+    }
+}
+
+const WEIGHT_STAT: &str = "weight";
+
+fn parse_stat_bound(s: &str) -> Result<(String, f64)> {
+    let (stat, value) = s
+        .split_once('=')
+        .with_context(|| format!("expected STAT=VALUE but got {s}"))?;
+    let stat = stat.trim();
+    if stat != WEIGHT_STAT && !Bonuses::NAMES.contains(&stat) {
+        anyhow::bail!(
+            "unknown stat {stat}, expected {WEIGHT_STAT} or one of {}",
+            Bonuses::NAMES.join(", ")
+        );
+    }
+    Ok((stat.to_string(), value.trim().parse()?))
+}
+
+/// the weight or bonus of the item, items without stats have a weight and bonuses of 0
+fn item_stat(item: &ItemDefinition, stat: &str) -> f64 {
+    if stat == WEIGHT_STAT {
+        item.weight.unwrap_or(0.0)
+    } else {
+        f64::from(item.bonuses_or_default().get(stat).unwrap_or(0))
+    }
+}
+
 #[derive(Debug, Clone, ValueEnum)]
 enum PrintFormat {
     /// plain text
     Basic,
+    /// array of full records in JSON format
+    Json,
     /// array of item ids in JSON format
     JsonId,
     /// array of item ids and names in JSON format
     JsonIdNameTuple,
 }
 
+#[derive(Debug, Clone, ValueEnum)]
+enum ReportFormat {
+    /// plain text
+    Basic,
+    /// JSON
+    Json,
+}
+
+#[derive(Debug, Clone, ValueEnum)]
+enum ValidateFormat {
+    /// plain text
+    Basic,
+    /// JSON
+    Json,
+    /// SARIF 2.1.0 log
+    Sarif,
+}
+
 fn main() {
     let cli = Cli::parse();
 
@@ -67,7 +1182,299 @@ fn main() {
 
     let result = match &cli.command {
         Commands::Debug => debug(),
-        Commands::PrintItems { format, find_items } => print_items(format, find_items),
+        Commands::PrintItems {
+            format,
+            bonuses,
+            find_items,
+            stat_bounds,
+        } => print_items(format, *bonuses, find_items, stat_bounds),
+        Commands::ModifyItems {
+            dry_run,
+            find_items,
+            edits,
+        } => modify_items(*dry_run, find_items, edits),
+        Commands::PrintDrops {
+            format,
+            drops_path,
+            find_npcs,
+            find_items,
+        } => print_drops(format, drops_path, find_npcs, find_items),
+        Commands::PrintShops {
+            format,
+            find_shops,
+            find_items,
+        } => print_shops(format, find_shops, find_items),
+        Commands::AuditShops {
+            format,
+            shops_path,
+            items_path,
+        } => audit_shops(format, shops_path, items_path),
+        Commands::PrintNpcs {
+            format,
+            xml,
+            source,
+            cache_dir,
+            find_npc_definitions,
+        } => match source {
+            NpcSource::Definitions => print_npcs(format, *xml, find_npc_definitions),
+            NpcSource::Cache if *xml => Err(anyhow::anyhow!(
+                "--xml can only print npcs from the definitions"
+            )),
+            NpcSource::Cache => print_cache_npcs(format, cache_dir, find_npc_definitions),
+        },
+        Commands::PrintObjects {
+            format,
+            find_objects,
+        } => print_objects(format, find_objects),
+        Commands::PrintSpawns {
+            format,
+            spawns_path,
+            find_npcs,
+            find_spawns,
+            density,
+        } => print_spawns(format, spawns_path, find_npcs, find_spawns, *density),
+        Commands::PrintEquipment {
+            format,
+            equipment_file,
+            slot,
+            find_items,
+        } => print_equipment(format, equipment_file, slot, find_items),
+        Commands::ExportEquipment {
+            equipment_file,
+            json_path,
+        } => export_equipment(equipment_file, json_path),
+        Commands::ImportEquipment {
+            equipment_file,
+            json_path,
+        } => import_equipment(equipment_file, json_path),
+        Commands::Path {
+            format,
+            cache_dir,
+            from,
+            to,
+            max_steps,
+        } => find_path(format, cache_dir, *from, *to, *max_steps),
+        Commands::CheckSpawns {
+            format,
+            cache_dir,
+            spawns_path,
+            drops_path,
+            doors_path,
+        } => check_spawns(format, cache_dir, spawns_path, drops_path, doors_path),
+        Commands::PrintCharacter {
+            format,
+            characters_dir,
+            items_path,
+            name,
+        } => print_character(format, characters_dir, items_path, name),
+        Commands::EditCharacter {
+            characters_dir,
+            name,
+            set_xp,
+            add_bank,
+            items_path,
+            position,
+            dry_run,
+        } => edit_character(
+            characters_dir,
+            name,
+            set_xp,
+            add_bank,
+            items_path,
+            *position,
+            *dry_run,
+        ),
+        Commands::Economy {
+            format,
+            characters_dir,
+            items_path,
+            top,
+        } => print_economy(format, characters_dir, items_path, *top),
+        Commands::MigrateItem {
+            from,
+            to,
+            mapping_path,
+            data_dir,
+            item_ids_dir,
+            report_path,
+            dry_run,
+        } => migrate_item(
+            from.zip(*to),
+            mapping_path.as_deref(),
+            data_dir,
+            item_ids_dir,
+            report_path,
+            *dry_run,
+        ),
+        Commands::Cache { command } => match command {
+            CacheCommand::List { format, find_files } => list_cache_files(format, find_files),
+            CacheCommand::DiffItems {
+                format,
+                cache_dir,
+                items_path,
+            } => diff_cache_items(format, cache_dir, items_path),
+            CacheCommand::RenderMap {
+                cache_dir,
+                region,
+                area,
+                height,
+                scale,
+                output_dir,
+                map_overlays,
+            } => render_map(
+                cache_dir,
+                region,
+                area,
+                *height,
+                *scale,
+                output_dir,
+                map_overlays,
+            ),
+            CacheCommand::Extract {
+                find_files,
+                output_dir,
+            } => extract_cache_files(find_files, output_dir),
+        },
+        Commands::Highscores {
+            characters_dir,
+            items_path,
+            output_dir,
+            top,
+        } => write_highscores(characters_dir, items_path, output_dir, *top),
+        Commands::Accounts { command } => match command {
+            AccountsCommand::Rehash {
+                format,
+                characters_dir,
+                backup_dir,
+                dry_run,
+            } => rehash_accounts(format, characters_dir, backup_dir, *dry_run),
+            AccountsCommand::Bulk {
+                format,
+                characters_dir,
+                name,
+                rights,
+                bot,
+                area,
+                owns,
+                give,
+                items_path,
+                reset_position,
+                clear,
+                config_path,
+                backup_dir,
+                dry_run,
+            } => {
+                let selector = Selector {
+                    name: name.clone(),
+                    rights: *rights,
+                    bot: *bot,
+                    area: *area,
+                    owns: *owns,
+                };
+                let respawn = if *reset_position {
+                    bulk::respawn(config_path).map(Some)
+                } else {
+                    Ok(None)
+                };
+                let gifts = give
+                    .iter()
+                    .map(|&(item_id, amount)| {
+                        let item_id = item_definition::bank_item_id(items_path, item_id)?;
+                        Ok(Action::GiveItem { item_id, amount })
+                    })
+                    .collect::<Result<Vec<_>>>();
+                respawn.and_then(|respawn| {
+                    let actions = gifts?
+                        .into_iter()
+                        .chain(respawn.map(Action::MovePlayer))
+                        .chain(clear.iter().cloned().map(Action::ClearKey))
+                        .collect_vec();
+                    bulk_accounts(
+                        format,
+                        characters_dir,
+                        &selector,
+                        &actions,
+                        backup_dir,
+                        *dry_run,
+                    )
+                })
+            }
+            AccountsCommand::Anonymize {
+                characters_dir,
+                output_dir,
+                pseudonyms_path,
+                names,
+            } => anonymize_accounts(characters_dir, output_dir, pseudonyms_path, names),
+            AccountsCommand::Check {
+                format,
+                characters_dir,
+                items_path,
+                repair,
+                backup_dir,
+            } => check_accounts(format, characters_dir, items_path, *repair, backup_dir),
+        },
+        Commands::Moderation { command } => match command {
+            ModerationCommand::List {
+                format,
+                data_dir,
+                list,
+            } => list_moderation(format, data_dir, *list),
+            ModerationCommand::Add {
+                data_dir,
+                list,
+                entries,
+                duration,
+                reason,
+            } => add_moderation(data_dir, *list, entries, *duration, reason.as_deref()),
+            ModerationCommand::Remove {
+                data_dir,
+                list,
+                entries,
+            } => remove_moderation(data_dir, *list, entries),
+            ModerationCommand::Expire { data_dir, dry_run } => {
+                expire_moderation(data_dir, *dry_run)
+            }
+        },
+        Commands::Migrate { migration } => match migration {
+            Migration::Npcs {
+                format,
+                definitions_path,
+                npc_list_path,
+                output_dir,
+                layout_path,
+                regenerate,
+            } => {
+                if *regenerate {
+                    regenerate_npcs(output_dir, layout_path, definitions_path, npc_list_path)
+                } else {
+                    migrate_npcs(
+                        format,
+                        definitions_path,
+                        npc_list_path,
+                        output_dir,
+                        layout_path,
+                    )
+                }
+            }
+        },
+        Commands::Validate { format, data_dir } => validate_data(format, data_dir),
+        Commands::SimulateDrops {
+            format,
+            npc,
+            kills,
+            seed,
+            drops_path,
+            npc_list_path,
+            items_path,
+        } => simulate_drops(
+            format,
+            *npc,
+            *kills,
+            *seed,
+            drops_path,
+            npc_list_path,
+            items_path,
+        ),
     };
 
     match result {
@@ -79,12 +1486,48 @@ fn main() {
     }
 }
 
-fn print_items(format: &PrintFormat, item_search: &FindItems) -> Result<()> {
-    let items = fetch_items(item_search)?;
+#[derive(Serialize, Debug)]
+struct ItemBonusRow<'a> {
+    id: i32,
+    name: &'a str,
+    weight: Option<f64>,
+    #[serde(flatten)]
+    bonuses: Bonuses,
+}
+
+fn print_items(
+    format: &PrintFormat,
+    bonuses: bool,
+    item_search: &FindItems,
+    stat_bounds: &StatBounds,
+) -> Result<()> {
+    let mut items = load_items(&item_search.items_path)?;
+    if bonuses || stat_bounds.has_bounds() {
+        item_definition::join_stats(&mut items, &item_stats::load_all(&stat_bounds.stats_path)?);
+    }
+    // stat bounds on their own search every item
+    if item_search.has_selectors() || !stat_bounds.has_bounds() {
+        items = select_items(item_search, &items)?;
+    }
+    items.retain(|item| stat_bounds.is_match(item));
     let mut sorted_items = items.iter().collect_vec();
     sorted_items.sort_by_key(|i| i.id);
 
+    let bonus_rows = || {
+        sorted_items
+            .iter()
+            .map(|item| ItemBonusRow {
+                id: item.id,
+                name: item.name.as_deref().unwrap_or("unnamed"),
+                weight: item.weight,
+                bonuses: item.bonuses_or_default(),
+            })
+            .collect_vec()
+    };
+
     let s = match format {
+        PrintFormat::Basic if bonuses => format_bonus_table(&bonus_rows()),
+        PrintFormat::Json if bonuses => serde_json::to_string(&bonus_rows())?,
         PrintFormat::Basic => sorted_items
             .iter()
             .map(|item| {
@@ -97,6 +1540,7 @@ fn print_items(format: &PrintFormat, item_search: &FindItems) -> Result<()> {
             })
             .collect_vec()
             .join("\n"),
+        PrintFormat::Json => serde_json::to_string(&sorted_items)?,
         PrintFormat::JsonId => {
             serde_json::to_string(&sorted_items.iter().map(|item| item.id).collect_vec())?
         }
@@ -112,59 +1556,2280 @@ fn print_items(format: &PrintFormat, item_search: &FindItems) -> Result<()> {
     Ok(())
 }
 
-fn fetch_items(find_items: &FindItems) -> Result<HashSet<ItemDefinition>> {
-    let data_dir = find_items.items_path.as_path();
-    let patterns = &find_items
-        .regex_pattern
+/// formats the rows like the equipment screen, grouping attack, defence and other bonuses
+fn format_bonus_table(rows: &[ItemBonusRow]) -> String {
+    let name_width = rows
         .iter()
-        .map(|p| match Regex::new(p) {
-            Ok(regex) => regex,
-            Err(e) => {
-                log::error!("could not parse pattern as regular expression: {p} due to error {e}");
-                panic!("")
-            }
-        })
-        .collect_vec();
+        .map(|row| row.name.chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(4);
+    let columns = ["stab", "slash", "crush", "magic", "range"]
+        .map(|column| format!("{column:>5}"))
+        .join(" ");
+    let groups = format!(
+        "{:>5} | {:<name_width$} | {:>6} | {:^29} | {:^29} | {:^11}",
+        "", "", "", "attack", "defence", "other"
+    );
+    let header = format!(
+        "{:>5} | {:<name_width$} | {:>6} | {columns} | {columns} | {:>5} {:>5}",
+        "id", "name", "weight", "str", "pray"
+    );
 
-    let mut desired_ids = HashSet::new();
-    for json_path in &find_items.ids_json {
-        let ids: Vec<i32> = serde_json::from_str(&fs::read_to_string(json_path)?)?;
-        desired_ids.extend(ids);
+    let lines = rows.iter().map(|row| {
+        let weight = row
+            .weight
+            .map_or(String::new(), |weight| weight.to_string());
+        let values = row.bonuses.values().map(|value| format!("{value:>5}"));
+        let (attack, rest) = values.split_at(5);
+        let (defence, other) = rest.split_at(5);
+        format!(
+            "{:>5} | {:<name_width$} | {:>6} | {} | {} | {}",
+            row.id,
+            row.name,
+            weight,
+            attack.join(" "),
+            defence.join(" "),
+            other.join(" ")
+        )
+    });
+
+    [groups.trim_end().to_string(), header]
+        .into_iter()
+        .chain(lines)
+        .join("\n")
+}
+
+fn modify_items(dry_run: bool, item_search: &FindItems, edits: &ItemEdits) -> Result<()> {
+    let modifications = edits.modifications();
+    if modifications.is_empty() {
+        anyhow::bail!("no modifications were specified");
     }
 
-    for json_path in &find_items.id_name_tuples_json {
-        let tuples: Vec<(i32, String)> = serde_json::from_str(&fs::read_to_string(json_path)?)?;
-        let ids: Vec<i32> = tuples.iter().map(|t| t.0).collect();
-        desired_ids.extend(ids);
+    let items = fetch_items(item_search)?;
+    let item_ids = items.iter().map(|item| item.id).sorted().collect_vec();
+    log::info!("modifying {} items...", item_ids.len());
+
+    let mut changed = 0;
+    for item_id in item_ids {
+        let change = modify(&item_search.items_path, item_id, &modifications)?;
+        if change.is_noop() {
+            continue;
+        }
+        changed += 1;
+        if dry_run {
+            print!("{}", change.unified_diff());
+        } else {
+            change.write()?;
+        }
+    }
+
+    if dry_run {
+        log::info!("{changed} files would be changed");
+    } else {
+        log::info!("{changed} files changed");
+    }
+    Ok(())
+}
+
+#[derive(Serialize, Debug)]
+struct DropRow<'a> {
+    npc_id: i32,
+    npc_name: &'a str,
+    item_id: i32,
+    item_name: &'a str,
+    chance: i32,
+    rate: String,
+    min_amount: i32,
+    max_amount: i32,
+}
+
+fn print_drops(
+    format: &PrintFormat,
+    drops_path: &Path,
+    find_npcs: &FindNpcs,
+    find_items: &FindItems,
+) -> Result<()> {
+    if !find_npcs.has_selectors() && !find_items.has_selectors() {
+        anyhow::bail!("no npcs or items were specified");
+    }
+
+    let drops = npc_drops::load_all(drops_path)?;
+    let npc_names = npc_data::names_by_id(&npc_data::load_all(&find_npcs.npc_list_path)?);
+    let items = load_items(&find_items.items_path)?;
+    let item_names: HashMap<i32, &str> = items
+        .iter()
+        .map(|item| (item.id, item.name.as_deref().unwrap_or("unnamed")))
+        .collect();
+
+    let npc_ids = find_npcs
+        .has_selectors()
+        .then(|| find_npcs.select(&npc_names))
+        .transpose()?;
+    let item_ids: Option<HashSet<i32>> = find_items
+        .has_selectors()
+        .then(|| select_items(find_items, &items))
+        .transpose()?
+        .map(|selected| selected.iter().map(|item| item.id).collect());
+
+    let fallback = npc_drops::default_drops();
+    let mut tables = drops
+        .iter()
+        .map(|npc| (npc.id, npc.items.as_slice()))
+        .collect_vec();
+    // npcs without a table still drop the server's default table
+    if let Some(npc_ids) = &npc_ids {
+        for npc_id in npc_ids {
+            if npc_drops::drops_for_npc(&drops, *npc_id).is_none() {
+                tables.push((*npc_id, fallback.as_slice()));
+            }
+        }
+    }
+    tables.sort_by_key(|(npc_id, _)| *npc_id);
+
+    let rows = tables
+        .iter()
+        .filter(|(npc_id, _)| npc_ids.as_ref().is_none_or(|ids| ids.contains(npc_id)))
+        .flat_map(|(npc_id, table)| table.iter().map(move |drop| (*npc_id, drop)))
+        .filter(|(_, drop)| {
+            item_ids
+                .as_ref()
+                .is_none_or(|ids| ids.contains(&drop.item_id))
+        })
+        .map(|(npc_id, drop)| DropRow {
+            npc_id,
+            npc_name: npc_names.get(&npc_id).map_or("unknown", String::as_str),
+            item_id: drop.item_id,
+            item_name: item_names.get(&drop.item_id).copied().unwrap_or("unknown"),
+            chance: drop.chance,
+            rate: drop.rate(),
+            min_amount: drop.min_amount(),
+            max_amount: drop.max_amount(),
+        })
+        .collect_vec();
+
+    let s = match format {
+        PrintFormat::Basic => rows
+            .iter()
+            .chunk_by(|row| (row.npc_id, row.npc_name))
+            .into_iter()
+            .map(|((npc_id, npc_name), drops)| {
+                let drops = drops
+                    .map(|row| {
+                        format!(
+                            "    {0} | {1} | {2} | {3}-{4}",
+                            row.item_id, row.item_name, row.rate, row.min_amount, row.max_amount
+                        )
+                    })
+                    .join("\n");
+                format!("{npc_id} | {npc_name}\n{drops}")
+            })
+            .join("\n"),
+        PrintFormat::Json => serde_json::to_string(&rows)?,
+        PrintFormat::JsonId => {
+            serde_json::to_string(&rows.iter().map(|row| row.npc_id).dedup().collect_vec())?
+        }
+        PrintFormat::JsonIdNameTuple => serde_json::to_string(
+            &rows
+                .iter()
+                .map(|row| (row.npc_id, row.npc_name))
+                .dedup()
+                .collect_vec(),
+        )?,
+    };
+
+    println!("{s}");
+    Ok(())
+}
+
+#[derive(Serialize, Debug)]
+struct SimulatedDrop<'a> {
+    item_id: i32,
+    item_name: &'a str,
+    chance: i32,
+    table_rate: f64,
+    observed_rate: f64,
+    average_amount: f64,
+    gp_per_kill: f64,
+    /// kills to first drop at the 50th, 90th and 99th percentiles
+    kills_to_first_drop: [Option<u64>; 3],
+}
+
+#[derive(Serialize, Debug)]
+struct DropSimulationReport<'a> {
+    npc_id: i32,
+    npc_name: &'a str,
+    kills: u64,
+    gp_per_kill: f64,
+    drops: Vec<SimulatedDrop<'a>>,
+}
+
+fn simulate_drops(
+    format: &ReportFormat,
+    npc_id: i32,
+    kills: u64,
+    seed: Option<u64>,
+    drops_path: &Path,
+    npc_list_path: &Path,
+    items_path: &Path,
+) -> Result<()> {
+    let drops = npc_drops::load_all(drops_path)?;
+    let npc_names = npc_data::names_by_id(&npc_data::load_all(npc_list_path)?);
+    let items: HashMap<i32, ItemDefinition> = load_items(items_path)?
+        .into_iter()
+        .map(|item| (item.id, item))
+        .collect();
+
+    let fallback = npc_drops::default_drops();
+    let table = match npc_drops::drops_for_npc(&drops, npc_id) {
+        Some(npc) => npc.items.as_slice(),
+        None => {
+            log::warn!("npc {npc_id} has no drop table, using the server's default table");
+            fallback.as_slice()
+        }
+    };
+
+    log::info!("simulating {kills} kills...");
+    let stats = drop_simulator::simulate(table, kills, seed);
+
+    let simulated = table
+        .iter()
+        .zip(&stats)
+        .map(|(drop, entry)| {
+            let item = items.get(&drop.item_id);
+            let value = item.map_or(0, |item| item.value);
+            let gp_per_kill = if kills == 0 {
+                0.0
+            } else {
+                entry.total_amount as f64 * f64::from(value) / kills as f64
+            };
+            SimulatedDrop {
+                item_id: drop.item_id,
+                item_name: item
+                    .and_then(|item| item.name.as_deref())
+                    .unwrap_or("unknown"),
+                chance: drop.chance,
+                table_rate: drop.probability(),
+                observed_rate: entry.observed_rate(kills),
+                average_amount: if entry.drops == 0 {
+                    0.0
+                } else {
+                    entry.total_amount as f64 / entry.drops as f64
+                },
+                gp_per_kill,
+                kills_to_first_drop: [50.0, 90.0, 99.0]
+                    .map(|percentile| entry.kills_to_first_drop(percentile)),
+            }
+        })
+        .collect_vec();
+
+    let report = DropSimulationReport {
+        npc_id,
+        npc_name: npc_names.get(&npc_id).map_or("unknown", String::as_str),
+        kills,
+        gp_per_kill: simulated.iter().map(|drop| drop.gp_per_kill).sum(),
+        drops: simulated,
+    };
+
+    let s = match format {
+        ReportFormat::Basic => {
+            let format_kills =
+                |kills: Option<u64>| kills.map_or("-".to_string(), |k| k.to_string());
+            let format_rate = |rate: f64| match rate {
+                0.0 => "never".to_string(),
+                rate => format!("1/{:.1}", 1.0 / rate),
+            };
+            let lines = report
+                .drops
+                .iter()
+                .map(|drop| {
+                    format!(
+                        "{0} | {1} | table {2} | observed {3} | avg amount {4:.1} | {5:.2} gp/kill | first drop p50 {6} p90 {7} p99 {8}",
+                        drop.item_id,
+                        drop.item_name,
+                        format_rate(drop.table_rate),
+                        format_rate(drop.observed_rate),
+                        drop.average_amount,
+                        drop.gp_per_kill,
+                        format_kills(drop.kills_to_first_drop[0]),
+                        format_kills(drop.kills_to_first_drop[1]),
+                        format_kills(drop.kills_to_first_drop[2]),
+                    )
+                })
+                .join("\n");
+            format!(
+                "{0} | {1} | {2} kills | {3:.2} gp/kill\n{lines}",
+                report.npc_id, report.npc_name, report.kills, report.gp_per_kill
+            )
+        }
+        ReportFormat::Json => serde_json::to_string(&report)?,
+    };
+
+    println!("{s}");
+    Ok(())
+}
+
+#[derive(Serialize, Debug)]
+struct ShopRow<'a> {
+    id: i32,
+    name: &'a str,
+    buy_modifier: i32,
+    sell_modifier: i32,
+    items: Vec<ShopItemRow<'a>>,
+}
+
+#[derive(Serialize, Debug)]
+struct ShopItemRow<'a> {
+    item_id: i32,
+    item_name: &'a str,
+    amount: i32,
+    buy_price: i32,
+    sell_price: i32,
+}
+
+fn print_shops(format: &PrintFormat, find_shops: &FindShops, find_items: &FindItems) -> Result<()> {
+    let shops = shops::load_all(&find_shops.shops_path)?;
+    let items = load_items(&find_items.items_path)?;
+    let item_ids: Option<HashSet<i32>> = find_items
+        .has_selectors()
+        .then(|| select_items(find_items, &items))
+        .transpose()?
+        .map(|selected| selected.iter().map(|item| item.id).collect());
+    let items: HashMap<i32, ItemDefinition> =
+        items.into_iter().map(|item| (item.id, item)).collect();
+    let patterns = compile_patterns(&find_shops.shop_pattern)?;
+
+    let rows = shops
+        .iter()
+        .filter(|shop| {
+            !find_shops.has_selectors()
+                || find_shops.shop_id.contains(&shop.id)
+                || patterns.iter().any(|pattern| pattern.is_match(&shop.name))
+        })
+        .filter(|shop| {
+            item_ids
+                .as_ref()
+                .is_none_or(|ids| shop.stock().any(|stocked| ids.contains(&stocked.item_id)))
+        })
+        .sorted_by_key(|shop| shop.id)
+        .map(|shop| ShopRow {
+            id: shop.id,
+            name: &shop.name,
+            buy_modifier: shop.buy_modifier,
+            sell_modifier: shop.sell_modifier,
+            items: shop
+                .stock()
+                .map(|stocked| {
+                    let item = items.get(&stocked.item_id);
+                    let unnoted = items.get(&shops::unnoted_id(&items, stocked.item_id));
+                    ShopItemRow {
+                        item_id: stocked.item_id,
+                        item_name: item
+                            .and_then(|item| item.name.as_deref())
+                            .unwrap_or("unknown"),
+                        amount: stocked.item_amount,
+                        buy_price: shop.buy_price(item),
+                        sell_price: shop.sell_price(unnoted),
+                    }
+                })
+                .collect(),
+        })
+        .collect_vec();
+
+    let s = match format {
+        PrintFormat::Basic => rows
+            .iter()
+            .map(|shop| {
+                let stock = shop
+                    .items
+                    .iter()
+                    .map(|item| {
+                        format!(
+                            "    {0} | {1} | x{2} | buy {3} | sell {4}",
+                            item.item_id,
+                            item.item_name,
+                            item.amount,
+                            item.buy_price,
+                            item.sell_price
+                        )
+                    })
+                    .join("\n");
+                format!(
+                    "{0} | {1} | buy modifier {2} | sell modifier {3}\n{stock}",
+                    shop.id, shop.name, shop.buy_modifier, shop.sell_modifier
+                )
+            })
+            .join("\n"),
+        PrintFormat::Json => serde_json::to_string(&rows)?,
+        PrintFormat::JsonId => {
+            serde_json::to_string(&rows.iter().map(|shop| shop.id).collect_vec())?
+        }
+        PrintFormat::JsonIdNameTuple => {
+            serde_json::to_string(&rows.iter().map(|shop| (shop.id, shop.name)).collect_vec())?
+        }
+    };
+
+    println!("{s}");
+    Ok(())
+}
+
+fn audit_shops(format: &ReportFormat, shops_path: &Path, items_path: &Path) -> Result<()> {
+    let shops = shops::load_all(shops_path)?;
+    let items: HashMap<i32, ItemDefinition> = load_items(items_path)?
+        .into_iter()
+        .map(|item| (item.id, item))
+        .collect();
+
+    let problems = shops::audit(&shops, &items);
+    log::info!("found {} problems in {} shops", problems.len(), shops.len());
+
+    let s = match format {
+        ReportFormat::Basic => problems
+            .iter()
+            .map(|problem| problem.to_string())
+            .join("\n"),
+        ReportFormat::Json => serde_json::to_string(&problems)?,
+    };
+
+    println!("{s}");
+    Ok(())
+}
+
+fn print_npcs(format: &PrintFormat, xml: bool, npc_search: &FindNpcDefinitions) -> Result<()> {
+    let definitions = npc_definition::load_all(&npc_search.definitions_path)?;
+    let mut npcs = select_npc_definitions(npc_search, &definitions, |npc: &NpcDefinition| {
+        (npc.id, npc.name.as_deref())
+    })?;
+    npcs.sort_by_key(|npc| npc.id);
+
+    if xml {
+        // the layout has no trailing newline
+        print!(
+            "{}",
+            npc_definition::to_xml_string(&npcs.into_iter().cloned().collect_vec())
+        );
+        return Ok(());
+    }
+
+    let s = match format {
+        PrintFormat::Basic => npcs
+            .iter()
+            .map(|npc| {
+                format!(
+                    "{0} | {1} (level {2}) | {3}",
+                    npc.id,
+                    npc.name.as_deref().unwrap_or(""),
+                    npc.combat,
+                    npc.examine.as_deref().unwrap_or("")
+                )
+            })
+            .join("\n"),
+        PrintFormat::Json => serde_json::to_string(&npcs)?,
+        PrintFormat::JsonId => serde_json::to_string(&npcs.iter().map(|npc| npc.id).collect_vec())?,
+        PrintFormat::JsonIdNameTuple => serde_json::to_string(
+            &npcs
+                .iter()
+                .map(|npc| (npc.id, npc.name.as_deref().unwrap_or("unnamed")))
+                .collect_vec(),
+        )?,
+    };
+
+    println!("{s}");
+    Ok(())
+}
+
+fn print_cache_npcs(
+    format: &PrintFormat,
+    cache_dir: &Path,
+    npc_search: &FindNpcDefinitions,
+) -> Result<()> {
+    let cache = IndexedFileSystem::open(cache_dir)?;
+    let definitions = npc_decoder::decode_all(&cache)?;
+    let npcs = select_npc_definitions(npc_search, &definitions, |npc: &CacheNpcDefinition| {
+        (npc.id, npc.name.as_deref())
+    })?;
+
+    let s = match format {
+        PrintFormat::Basic => npcs
+            .iter()
+            .map(|npc| {
+                let level = npc
+                    .combat_level
+                    .map_or(String::new(), |level| format!(" (level {level})"));
+                format!(
+                    "{0} | {1}{2} | {3} | {4}",
+                    npc.id,
+                    npc.name.as_deref().unwrap_or(""),
+                    level,
+                    npc.interactions.iter().flatten().join(", "),
+                    npc.description.as_deref().unwrap_or("")
+                )
+            })
+            .join("\n"),
+        PrintFormat::Json => serde_json::to_string(&npcs)?,
+        PrintFormat::JsonId => serde_json::to_string(&npcs.iter().map(|npc| npc.id).collect_vec())?,
+        PrintFormat::JsonIdNameTuple => serde_json::to_string(
+            &npcs
+                .iter()
+                .map(|npc| (npc.id, npc.name.as_deref().unwrap_or("unnamed")))
+                .collect_vec(),
+        )?,
+    };
+
+    println!("{s}");
+    Ok(())
+}
+
+fn print_objects(format: &PrintFormat, find_objects: &FindObjects) -> Result<()> {
+    let cache = IndexedFileSystem::open(&find_objects.cache_dir)?;
+    let definitions = object_decoder::decode_all(&cache)?;
+    let objects = find_objects.select(&definitions)?;
+
+    let s = match format {
+        PrintFormat::Basic => objects
+            .iter()
+            .map(|object| {
+                format!(
+                    "{0} | {1} | {2} | {3}x{4}",
+                    object.id,
+                    object.name.as_deref().unwrap_or(""),
+                    object.menu_actions().join(", "),
+                    object.width,
+                    object.length
+                )
+            })
+            .join("\n"),
+        PrintFormat::Json => serde_json::to_string(&objects)?,
+        PrintFormat::JsonId => {
+            serde_json::to_string(&objects.iter().map(|object| object.id).collect_vec())?
+        }
+        PrintFormat::JsonIdNameTuple => serde_json::to_string(
+            &objects
+                .iter()
+                .map(|object| (object.id, object.name.as_deref().unwrap_or("unnamed")))
+                .collect_vec(),
+        )?,
+    };
+
+    println!("{s}");
+    Ok(())
+}
+
+#[derive(Serialize, Debug)]
+struct SpawnRow<'a> {
+    npc_id: i32,
+    npc_name: &'a str,
+    x: i32,
+    y: i32,
+    height: i32,
+    region_id: i32,
+    walk: i32,
+    max_hit: i32,
+    attack: i32,
+    strength: i32,
+}
+
+fn print_spawns(
+    format: &PrintFormat,
+    spawns_path: &Path,
+    find_npcs: &FindNpcs,
+    find_spawns: &FindSpawns,
+    density: bool,
+) -> Result<()> {
+    let spawns = spawns::load_all(spawns_path)?;
+    let npc_names = npc_data::names_by_id(&npc_data::load_all(&find_npcs.npc_list_path)?);
+    let npc_ids = find_npcs
+        .has_selectors()
+        .then(|| find_npcs.select(&npc_names))
+        .transpose()?;
+
+    let matches = spawns
+        .iter()
+        .filter(|spawn| npc_ids.as_ref().is_none_or(|ids| ids.contains(&spawn.id)))
+        .filter(|spawn| find_spawns.is_match(spawn))
+        .sorted_by_key(|spawn| (spawn.id, spawn.height, spawn.x, spawn.y))
+        .collect_vec();
+    log::info!("found {} matching spawns", matches.len());
+
+    if density {
+        let density = spawns::region_density(matches.iter().copied());
+        let s = match format {
+            PrintFormat::Basic => density
+                .iter()
+                .sorted_by_key(|(region_id, count)| (std::cmp::Reverse(**count), **region_id))
+                .map(|(region_id, count)| {
+                    let (x, y) = spawns::region_base(*region_id);
+                    format!("{region_id} | ({x}, {y}) | {count}")
+                })
+                .join("\n"),
+            PrintFormat::Json => serde_json::to_string(&density)?,
+            PrintFormat::JsonId => serde_json::to_string(&density.keys().collect_vec())?,
+            PrintFormat::JsonIdNameTuple => serde_json::to_string(&density.iter().collect_vec())?,
+        };
+        println!("{s}");
+        return Ok(());
+    }
+
+    let rows = matches
+        .iter()
+        .map(|spawn| SpawnRow {
+            npc_id: spawn.id,
+            npc_name: npc_names.get(&spawn.id).map_or("unknown", String::as_str),
+            x: spawn.x,
+            y: spawn.y,
+            height: spawn.height,
+            region_id: spawn.region_id(),
+            walk: spawn.walk,
+            max_hit: spawn.max_hit,
+            attack: spawn.attack,
+            strength: spawn.strength,
+        })
+        .collect_vec();
+
+    let s = match format {
+        PrintFormat::Basic => rows
+            .iter()
+            .map(|row| {
+                format!(
+                    "{0} | {1} | ({2}, {3}, {4}) | region {5}",
+                    row.npc_id, row.npc_name, row.x, row.y, row.height, row.region_id
+                )
+            })
+            .join("\n"),
+        PrintFormat::Json => serde_json::to_string(&rows)?,
+        PrintFormat::JsonId => {
+            serde_json::to_string(&rows.iter().map(|row| row.npc_id).dedup().collect_vec())?
+        }
+        PrintFormat::JsonIdNameTuple => serde_json::to_string(
+            &rows
+                .iter()
+                .map(|row| (row.npc_id, row.npc_name))
+                .dedup()
+                .collect_vec(),
+        )?,
+    };
+
+    println!("{s}");
+    Ok(())
+}
+
+#[derive(Serialize, Debug)]
+struct EquipmentRow<'a> {
+    item_id: i32,
+    item_name: &'a str,
+    slot: i32,
+    slot_name: Option<&'static str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    details: Option<&'a EquipmentDetails>,
+}
+
+fn print_equipment(
+    format: &PrintFormat,
+    equipment_file: &EquipmentFile,
+    slots: &[i32],
+    find_items: &FindItems,
+) -> Result<()> {
+    if slots.is_empty() && !find_items.has_selectors() {
+        anyhow::bail!("no slots or items were specified");
+    }
+
+    let entries = equipment_file.load()?;
+    let items = load_items(&find_items.items_path)?;
+    let item_names: HashMap<i32, &str> = items
+        .iter()
+        .map(|item| (item.id, item.name.as_deref().unwrap_or("unnamed")))
+        .collect();
+    let item_ids: Option<HashSet<i32>> = find_items
+        .has_selectors()
+        .then(|| select_items(find_items, &items))
+        .transpose()?
+        .map(|selected| selected.iter().map(|item| item.id).collect());
+
+    let rows = entries
+        .iter()
+        .filter(|entry| item_ids.as_ref().is_none_or(|ids| ids.contains(&entry.id)))
+        .filter(|entry| slots.is_empty() || slots.contains(&entry.slot))
+        .map(|entry| EquipmentRow {
+            item_id: entry.id,
+            item_name: item_names.get(&entry.id).copied().unwrap_or("unknown"),
+            slot: entry.slot,
+            slot_name: equipment::slot_name(entry.slot),
+            details: entry.details.as_ref(),
+        })
+        .collect_vec();
+
+    let s = match format {
+        PrintFormat::Basic => rows
+            .iter()
+            .map(|row| {
+                let mut line = format!(
+                    "{0} | {1} | {2}",
+                    row.item_id,
+                    row.item_name,
+                    row.slot_name.map_or(row.slot.to_string(), str::to_string)
+                );
+                if let Some(details) = row.details {
+                    let flags = [
+                        (details.two_handed, "two-handed"),
+                        (details.full_body, "full body"),
+                        (details.full_hat, "full hat"),
+                        (details.full_mask, "full mask"),
+                    ]
+                    .into_iter()
+                    .filter(|(set, _)| *set)
+                    .map(|(_, flag)| flag)
+                    .join(", ");
+                    let requirements = details
+                        .requirements
+                        .levels()
+                        .iter()
+                        .map(|(skill, level)| format!("{level} {skill}"))
+                        .join(", ");
+                    for column in [flags, requirements] {
+                        if !column.is_empty() {
+                            line.push_str(&format!(" | {column}"));
+                        }
+                    }
+                }
+                line
+            })
+            .join("\n"),
+        PrintFormat::Json => serde_json::to_string(&rows)?,
+        PrintFormat::JsonId => {
+            serde_json::to_string(&rows.iter().map(|row| row.item_id).collect_vec())?
+        }
+        PrintFormat::JsonIdNameTuple => serde_json::to_string(
+            &rows
+                .iter()
+                .map(|row| (row.item_id, row.item_name))
+                .collect_vec(),
+        )?,
+    };
+
+    println!("{s}");
+    Ok(())
+}
+
+fn export_equipment(equipment_file: &EquipmentFile, json_path: &Path) -> Result<()> {
+    let entries = equipment_file.load()?;
+    fs::write(json_path, serde_json::to_string_pretty(&entries)?)
+        .with_context(|| format!("could not write {}", json_path.display()))?;
+    log::info!(
+        "exported {} items to {}",
+        entries.len(),
+        json_path.display()
+    );
+    Ok(())
+}
+
+fn import_equipment(equipment_file: &EquipmentFile, json_path: &Path) -> Result<()> {
+    let contents = fs::read_to_string(json_path)
+        .with_context(|| format!("could not read {}", json_path.display()))?;
+    let entries: Vec<EquipmentEntry> = serde_json::from_str(&contents)
+        .with_context(|| format!("could not parse {}", json_path.display()))?;
+    equipment::save(
+        &equipment_file.equipment_path,
+        &entries,
+        equipment_file.layout.layout(),
+    )?;
+    log::info!(
+        "imported {} items into {}",
+        entries.len(),
+        equipment_file.equipment_path.display()
+    );
+    Ok(())
+}
+
+#[derive(Serialize, Debug)]
+struct CacheFileRow {
+    index: usize,
+    file: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    entry: Option<IndexEntry>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<IntegrityError>,
+}
+
+fn list_cache_files(format: &ReportFormat, find_files: &FindCacheFiles) -> Result<()> {
+    let cache = IndexedFileSystem::open(&find_files.cache_dir)?;
+    let rows = find_files
+        .select(&cache)?
+        .into_iter()
+        .map(|(index, file)| CacheFileRow {
+            index,
+            file,
+            entry: cache.entry(index, file).ok(),
+            error: cache.read(index, file).err(),
+        })
+        .collect_vec();
+    let errors = rows.iter().filter(|row| row.error.is_some()).count();
+    log::info!("listed {} files, {errors} could not be read", rows.len());
+
+    let s = match format {
+        ReportFormat::Basic => rows
+            .iter()
+            .map(|row| {
+                let entry = row.entry.map_or("missing".to_string(), |entry| {
+                    format!("{} bytes at sector {}", entry.size, entry.sector)
+                });
+                match &row.error {
+                    Some(error) => format!("{} | {} | {entry} | {error}", row.index, row.file),
+                    None => format!("{} | {} | {entry}", row.index, row.file),
+                }
+            })
+            .join("\n"),
+        ReportFormat::Json => serde_json::to_string(&rows)?,
+    };
+
+    println!("{s}");
+    Ok(())
+}
+
+fn extract_cache_files(find_files: &FindCacheFiles, output_dir: &Path) -> Result<()> {
+    let cache = IndexedFileSystem::open(&find_files.cache_dir)?;
+    let files = find_files.select(&cache)?;
+
+    let pb = ProgressBar::new(files.len().try_into().unwrap());
+    let mut extracted = 0;
+    let mut errors = 0;
+    for (index, file) in files {
+        pb.inc(1);
+        let data = match cache.read(index, file) {
+            Ok(data) => data,
+            Err(e) => {
+                log::error!("{e}");
+                errors += 1;
+                continue;
+            }
+        };
+        // unused file ids have no data
+        if data.is_empty() {
+            continue;
+        }
+        let dir = output_dir.join(index.to_string());
+        fs::create_dir_all(&dir).with_context(|| format!("could not create {}", dir.display()))?;
+        let path = dir.join(format!("{file}.dat"));
+        fs::write(&path, data).with_context(|| format!("could not write {}", path.display()))?;
+        extracted += 1;
+    }
+    pb.finish_and_clear();
+
+    log::info!("extracted {extracted} files to {}", output_dir.display());
+    if errors > 0 {
+        anyhow::bail!("{errors} files could not be read");
+    }
+    Ok(())
+}
+
+fn render_map(
+    cache_dir: &Path,
+    regions: &[i32],
+    areas: &[Area],
+    height: i32,
+    scale: u32,
+    output_dir: &Path,
+    map_overlays: &MapOverlays,
+) -> Result<()> {
+    if regions.is_empty() && areas.is_empty() {
+        anyhow::bail!("specify at least one --region or --area to render");
+    }
+    if !(0..map::MAP_PLANES).contains(&height) {
+        anyhow::bail!("expected a height between 0 and 3 but got {height}");
+    }
+
+    let cache = IndexedFileSystem::open(cache_dir)?;
+    let indices = map::decode_index(&cache)?;
+    let floors = floor_decoder::decode_all(&cache)?;
+    let objects = object_decoder::decode_all(&cache)?;
+    let markers = map_overlays.load_markers()?;
+
+    let mut images = Vec::new();
+    for region in regions {
+        let (x, y) = spawns::region_base(*region);
+        let area = Area::new(x, y, x + map::MAP_WIDTH - 1, y + map::MAP_WIDTH - 1);
+        images.push((format!("{region}.png"), area));
+    }
+    for area in areas {
+        let name = format!(
+            "{}_{}_{}_{}.png",
+            area.min_x, area.min_y, area.max_x, area.max_y
+        );
+        images.push((name, *area));
+    }
+    // fail before decoding any regions rather than after
+    for (name, area) in &images {
+        map_render::image_size(area, scale).with_context(|| format!("could not render {name}"))?;
+    }
+
+    // every region touched by one of the images
+    let mut region_ids = HashSet::new();
+    for (_, area) in &images {
+        for x in (area.min_x >> 6)..=(area.max_x >> 6) {
+            for y in (area.min_y >> 6)..=(area.max_y >> 6) {
+                region_ids.insert((x << 8) | y);
+            }
+        }
+    }
+    let mut decoded = HashMap::new();
+    for region_id in region_ids {
+        match indices.get(&region_id) {
+            Some(index) => {
+                decoded.insert(region_id, map::load_region(&cache, index)?);
+            }
+            None => log::warn!("region {region_id} has no map and is drawn black"),
+        }
+    }
+
+    fs::create_dir_all(output_dir)
+        .with_context(|| format!("could not create {}", output_dir.display()))?;
+    let renderer = MapRenderer {
+        regions: &decoded,
+        floors: &floors,
+        objects: &objects,
+        scale,
+    };
+    for (name, area) in images {
+        let path = output_dir.join(name);
+        renderer.render(&area, height, &markers)?.save_png(&path)?;
+        log::info!("wrote {}", path.display());
+    }
+    Ok(())
+}
+
+/// builds the clipping of every region in the cache, like the server does on startup
+fn load_collision_map(cache_dir: &Path) -> Result<CollisionMap> {
+    let cache = IndexedFileSystem::open(cache_dir)?;
+    let objects = object_decoder::decode_all(&cache)?;
+    let indices = map::decode_index(&cache)?;
+
+    log::info!("decoding {} regions...", indices.len());
+    let regions = indices
+        .values()
+        .map(|index| map::load_region(&cache, index))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(CollisionMap::build(&regions, &objects))
+}
+
+#[derive(Serialize, Debug)]
+struct PathReport {
+    from: (i32, i32, i32),
+    to: (i32, i32, i32),
+    steps: usize,
+    /// whether the destination is inside the 104x104 area searched by the server's PathFinder
+    within_scene: bool,
+    waypoints: Vec<(i32, i32)>,
+    tiles: Vec<(i32, i32)>,
+}
+
+fn find_path(
+    format: &ReportFormat,
+    cache_dir: &Path,
+    from: (i32, i32, i32),
+    to: (i32, i32, i32),
+    max_steps: usize,
+) -> Result<()> {
+    if from.2 != to.2 {
+        anyhow::bail!("paths can not change height, use stairs or ladders instead");
+    }
+    let map = load_collision_map(cache_dir)?;
+    let Some(tiles) = map.find_path((from.0, from.1), (to.0, to.1), from.2, max_steps) else {
+        anyhow::bail!(
+            "no path from {},{},{} to {},{},{} within {max_steps} steps",
+            from.0,
+            from.1,
+            from.2,
+            to.0,
+            to.1,
+            to.2
+        );
+    };
+
+    // the server searches the scene of the player, which starts 6 chunks south west of it
+    let scene_base = |coordinate: i32| ((coordinate >> 3) - 6) * 8;
+    let within_scene = (0..104).contains(&(to.0 - scene_base(from.0)))
+        && (0..104).contains(&(to.1 - scene_base(from.1)));
+    let report = PathReport {
+        from,
+        to,
+        steps: tiles.len(),
+        within_scene,
+        waypoints: collision::waypoints((from.0, from.1), &tiles),
+        tiles,
+    };
+
+    let s = match format {
+        ReportFormat::Basic => {
+            let mut lines = vec![format!(
+                "{} steps from {},{} to {},{} on height {}",
+                report.steps, from.0, from.1, to.0, to.1, from.2
+            )];
+            if !report.within_scene {
+                lines.push(
+                    "the destination is outside of the area searched by the server's PathFinder"
+                        .to_string(),
+                );
+            }
+            lines.extend(
+                report
+                    .waypoints
+                    .iter()
+                    .map(|(x, y)| format!("walk to {x},{y}")),
+            );
+            lines.join("\n")
+        }
+        ReportFormat::Json => serde_json::to_string(&report)?,
+    };
+
+    println!("{s}");
+    Ok(())
+}
+
+fn check_spawns(
+    format: &ReportFormat,
+    cache_dir: &Path,
+    spawns_path: &Path,
+    drops_path: &Path,
+    doors_path: &Path,
+) -> Result<()> {
+    let mut placements = Vec::new();
+    placements.extend(
+        spawns::load_all(spawns_path)?
+            .iter()
+            .map(|spawn| Placement {
+                kind: PlacementKind::NpcSpawn,
+                id: spawn.id,
+                x: spawn.x,
+                y: spawn.y,
+                height: spawn.height,
+            }),
+    );
+    placements.extend(
+        global_drops::load_all(drops_path)?
+            .iter()
+            .map(|drop| Placement {
+                kind: PlacementKind::GlobalDrop,
+                id: drop.id,
+                x: drop.item_x,
+                y: drop.item_y,
+                height: drop.height(),
+            }),
+    );
+    for door in doors::load_all(doors_path)? {
+        placements.extend(door.locations.iter().map(|location| Placement {
+            kind: PlacementKind::Door,
+            id: door.id,
+            x: location.x,
+            y: location.y,
+            height: location.height,
+        }));
+    }
+
+    let map = load_collision_map(cache_dir)?;
+    let problems = collision::check_placements(&map, placements.iter().copied());
+    log::info!(
+        "found {} problems in {} placements",
+        problems.len(),
+        placements.len()
+    );
+
+    let s = match format {
+        ReportFormat::Basic => problems
+            .iter()
+            .map(|problem| problem.to_string())
+            .join("\n"),
+        ReportFormat::Json => serde_json::to_string(&problems)?,
+    };
+    println!("{s}");
+
+    if !problems.is_empty() {
+        anyhow::bail!("{} placements are on blocked tiles", problems.len());
+    }
+    Ok(())
+}
+
+fn diff_cache_items(format: &ReportFormat, cache_dir: &Path, items_path: &Path) -> Result<()> {
+    let cache = IndexedFileSystem::open(cache_dir)?;
+    let cache_items = item_decoder::decode_all(&cache)?;
+    let items = load_items(items_path)?;
+    let differences = item_decoder::diff(&items, &cache_items);
+    log::info!(
+        "compared {} items with {} cache items, found {} differences",
+        items.len(),
+        cache_items.len(),
+        differences.len()
+    );
+
+    match format {
+        ReportFormat::Basic => {
+            for difference in &differences {
+                println!("{difference}");
+            }
+        }
+        ReportFormat::Json => println!("{}", serde_json::to_string(&differences)?),
+    }
+    Ok(())
+}
+
+fn migrate_npcs(
+    format: &ReportFormat,
+    definitions_path: &Path,
+    npc_list_path: &Path,
+    output_dir: &Path,
+    layout_path: &Path,
+) -> Result<()> {
+    let definitions = npc_definition::load_all(definitions_path)?;
+    let npc_list = npc_data::load_all(npc_list_path)?;
+    let merge = npc_migration::merge(&definitions, &npc_list);
+    npc_migration::check_round_trip(
+        &merge.npcs,
+        &merge.layout,
+        &fs::read_to_string(definitions_path)?,
+        &fs::read_to_string(npc_list_path)?,
+    )
+    .context("the migration would lose data, no files were written")?;
+
+    fs::create_dir_all(output_dir)
+        .with_context(|| format!("could not create {}", output_dir.display()))?;
+    let pb = ProgressBar::new(merge.npcs.len().try_into().unwrap());
+    for npc in &merge.npcs {
+        npc_migration::save(output_dir, npc)?;
+        pb.inc(1);
+    }
+    pb.finish_and_clear();
+    npc_migration::save_layout(layout_path, &merge.layout)?;
+    log::info!(
+        "wrote {} npcs to {} and their layout to {}",
+        merge.npcs.len(),
+        output_dir.display(),
+        layout_path.display()
+    );
+
+    for npc_id in &merge.duplicate_list_ids {
+        log::warn!(
+            "npc {npc_id} is listed more than once in npc.json, the server uses the first entry"
+        );
+    }
+    log::info!("found {} conflicting fields", merge.conflicts.len());
+
+    match format {
+        ReportFormat::Basic => {
+            for conflict in &merge.conflicts {
+                println!("{conflict}");
+            }
+        }
+        ReportFormat::Json => println!("{}", serde_json::to_string(&merge.conflicts)?),
+    }
+    Ok(())
+}
+
+fn regenerate_npcs(
+    npcs_dir: &Path,
+    layout_path: &Path,
+    definitions_path: &Path,
+    npc_list_path: &Path,
+) -> Result<()> {
+    let npcs = npc_migration::load_all(npcs_dir)?;
+    let layout = npc_migration::load_layout(layout_path)?;
+    let (definitions, npc_list) = npc_migration::split(&npcs, &layout);
+
+    npc_definition::save_all(definitions_path, &definitions)?;
+    log::info!(
+        "wrote {} npcs to {}",
+        definitions.len(),
+        definitions_path.display()
+    );
+    npc_data::save_all(npc_list_path, &npc_list)?;
+    log::info!(
+        "wrote {} npcs to {}",
+        npc_list.len(),
+        npc_list_path.display()
+    );
+    Ok(())
+}
+
+fn validate_data(format: &ValidateFormat, data_dir: &Path) -> Result<()> {
+    log::info!("validating {}...", data_dir.display());
+    let issues = validate::validate(&DataPaths::new(data_dir));
+
+    let s = match format {
+        ValidateFormat::Basic => issues.iter().map(|issue| issue.to_string()).join("\n"),
+        ValidateFormat::Json => serde_json::to_string(&issues)?,
+        ValidateFormat::Sarif => serde_json::to_string_pretty(&validate::to_sarif(&issues))?,
+    };
+    println!("{s}");
+
+    let errors = issues
+        .iter()
+        .filter(|issue| issue.severity == Severity::Error)
+        .count();
+    log::info!(
+        "found {errors} errors and {} warnings",
+        issues.len() - errors
+    );
+    if errors > 0 {
+        anyhow::bail!("validation failed with {errors} errors");
     }
+    Ok(())
+}
 
-    log::info!("searching items...");
-    let mut items = HashSet::new();
-    let filepaths = std::fs::read_dir(data_dir)?
+fn fetch_items(find_items: &FindItems) -> Result<Vec<ItemDefinition>> {
+    let items = load_items(&find_items.items_path)?;
+    select_items(find_items, &items)
+}
+
+fn load_items(items_path: &Path) -> Result<Vec<ItemDefinition>> {
+    log::info!("loading items...");
+    let filepaths = std::fs::read_dir(items_path)
+        .with_context(|| format!("could not read {}", items_path.display()))?
         .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
         .collect_vec();
     let pb = ProgressBar::new(filepaths.len().try_into().unwrap());
+    let mut items = Vec::with_capacity(filepaths.len());
     for path in filepaths {
-        if path.extension().map(|x| x.to_string_lossy().to_string()) == Some("json".to_string()) {
-            let definition: ItemDefinition = serde_json::from_str(&fs::read_to_string(path)?)?;
-            let name = &definition.name;
+        items.push(item_definition::load(&path)?);
+        pb.inc(1);
+    }
+
+    Ok(items)
+}
+
+fn select_items(find_items: &FindItems, items: &[ItemDefinition]) -> Result<Vec<ItemDefinition>> {
+    let patterns = compile_patterns(&find_items.regex_pattern)?;
+
+    let desired_ids = read_ids(&find_items.ids_json, &find_items.id_name_tuples_json)?;
+
+    log::info!("searching items...");
+    let items = items
+        .iter()
+        .filter(|definition| {
             // match against regex patterns
-            for pattern in patterns {
-                if pattern.is_match(name.as_deref().unwrap_or("")) {
-                    items.insert(definition.clone());
+            let name = definition.name.as_deref().unwrap_or("");
+            patterns.iter().any(|pattern| pattern.is_match(name))
+                // match against ids from id jsons
+                || desired_ids.contains(&definition.id)
+        })
+        .cloned()
+        .collect();
+
+    Ok(items)
+}
+
+/// selects the npcs of either source, `id_name` returns the id and name of an npc
+fn select_npc_definitions<'a, T>(
+    find_npcs: &FindNpcDefinitions,
+    definitions: &'a [T],
+    id_name: impl Fn(&T) -> (i32, Option<&str>),
+) -> Result<Vec<&'a T>> {
+    let patterns = compile_patterns(&find_npcs.regex_pattern)?;
+    let desired_ids = read_ids(&find_npcs.ids_json, &find_npcs.id_name_tuples_json)?;
+
+    log::info!("searching npcs...");
+    Ok(definitions
+        .iter()
+        .filter(|definition| {
+            let (id, name) = id_name(definition);
+            patterns
+                .iter()
+                .any(|pattern| pattern.is_match(name.unwrap_or("")))
+                || desired_ids.contains(&id)
+        })
+        .collect())
+}
+
+/// reads the ids from JSON files written by the json-id and json-id-name-tuple formats
+fn read_ids(ids_json: &[PathBuf], id_name_tuples_json: &[PathBuf]) -> Result<HashSet<i32>> {
+    let mut desired_ids = HashSet::new();
+    for json_path in ids_json {
+        let ids: Vec<i32> = serde_json::from_str(&fs::read_to_string(json_path)?)?;
+        desired_ids.extend(ids);
+    }
+
+    for json_path in id_name_tuples_json {
+        let tuples: Vec<(i32, String)> = serde_json::from_str(&fs::read_to_string(json_path)?)?;
+        let ids: Vec<i32> = tuples.iter().map(|t| t.0).collect();
+        desired_ids.extend(ids);
+    }
+
+    Ok(desired_ids)
+}
+
+fn compile_patterns(patterns: &[String]) -> Result<Vec<Regex>> {
+    patterns
+        .iter()
+        .map(|p| {
+            Regex::new(p)
+                .with_context(|| format!("could not parse pattern as regular expression: {p}"))
+        })
+        .collect()
+}
+
+fn debug() -> Result<()> {
+    Ok(())
+}
+
+#[derive(Serialize, Debug)]
+struct CharacterReport<'a> {
+    username: Option<&'a str>,
+    rights: i32,
+    position: Position,
+    skills: Vec<SkillRow>,
+    quest_stages: Vec<(&'static str, i32)>,
+    equipment: Vec<CharacterItemRow<'a>>,
+    inventory: Vec<CharacterItemRow<'a>>,
+    bank: Vec<CharacterItemRow<'a>>,
+    friends: Vec<String>,
+    ignores: Vec<String>,
+    properties: &'a [Property],
+}
+
+#[derive(Serialize, Debug)]
+struct SkillRow {
+    skill: String,
+    level: i32,
+    xp: i32,
+}
+
+#[derive(Serialize, Debug)]
+struct CharacterItemRow<'a> {
+    slot: usize,
+    item_id: i32,
+    item_name: &'a str,
+    amount: i32,
+}
+
+fn print_character(
+    format: &ReportFormat,
+    characters_dir: &Path,
+    items_path: &Path,
+    name: &str,
+) -> Result<()> {
+    let save = characters::load(&characters::save_path(characters_dir, name))?;
+    let item_names: HashMap<i32, String> = item_definition::load_all(items_path)?
+        .into_iter()
+        .filter_map(|item| Some((item.id, item.name?)))
+        .collect();
+    let item_rows = |items: Vec<ItemSlot>| {
+        items
+            .into_iter()
+            .sorted_by_key(|item| item.slot)
+            .map(|item| CharacterItemRow {
+                slot: item.slot,
+                item_id: item.item_id,
+                item_name: item_names.get(&item.item_id).map_or("", String::as_str),
+                amount: item.amount,
+            })
+            .collect_vec()
+    };
+
+    let report = CharacterReport {
+        username: save.username(),
+        rights: save.rights()?,
+        position: save.position()?,
+        skills: save
+            .skills
+            .iter()
+            .sorted_by_key(|skill| skill.skill)
+            .map(|skill| SkillRow {
+                skill: characters::skill_name(skill.skill),
+                level: skill.level,
+                xp: skill.xp,
+            })
+            .collect(),
+        quest_stages: save.quest_stages()?,
+        equipment: item_rows(
+            save.equipment
+                .iter()
+                .filter(|slot| slot.item_id >= 0)
+                .map(|slot| ItemSlot {
+                    slot: slot.slot,
+                    item_id: slot.item_id,
+                    amount: slot.amount,
+                    price: None,
+                })
+                .collect(),
+        ),
+        inventory: item_rows(save.inventory.clone()),
+        bank: item_rows(save.bank.clone()),
+        friends: save.friends.iter().map(|c| c.name()).collect(),
+        ignores: save.ignores.iter().map(|c| c.name()).collect(),
+        properties: &save.character,
+    };
+
+    let s = match format {
+        ReportFormat::Basic => {
+            let position = report.position;
+            let mut lines = vec![format!(
+                "{} (rights {}) at {},{},{}",
+                report.username.unwrap_or(name),
+                report.rights,
+                position.x,
+                position.y,
+                position.height
+            )];
+            lines.push("skills:".to_string());
+            lines.extend(report.skills.iter().map(|skill| {
+                format!(
+                    "  {:<13} {:>2} {:>10} xp",
+                    skill.skill, skill.level, skill.xp
+                )
+            }));
+            lines.push("quest stages:".to_string());
+            lines.extend(
+                report
+                    .quest_stages
+                    .iter()
+                    .map(|(quest, stage)| format!("  {quest} = {stage}")),
+            );
+            for (title, rows) in [
+                ("equipment", &report.equipment),
+                ("inventory", &report.inventory),
+                ("bank", &report.bank),
+            ] {
+                lines.push(format!("{title}:"));
+                lines.extend(rows.iter().map(|row| {
+                    format!(
+                        "  {:>3}: {} x{} ({})",
+                        row.slot, row.item_name, row.amount, row.item_id
+                    )
+                }));
+            }
+            lines.push(format!("friends: {}", report.friends.join(", ")));
+            lines.push(format!("ignores: {}", report.ignores.join(", ")));
+            lines.join("\n")
+        }
+        ReportFormat::Json => serde_json::to_string(&report)?,
+    };
+
+    println!("{s}");
+    Ok(())
+}
+
+fn edit_character(
+    characters_dir: &Path,
+    name: &str,
+    set_xp: &[(usize, i32)],
+    add_bank: &[(i32, i32)],
+    items_path: &Path,
+    position: Option<(i32, i32, i32)>,
+    dry_run: bool,
+) -> Result<()> {
+    if set_xp.is_empty() && add_bank.is_empty() && position.is_none() {
+        anyhow::bail!("no edits were specified");
+    }
+
+    let path = characters::save_path(characters_dir, name);
+    let original = fs::read_to_string(&path)
+        .with_context(|| format!("could not read character from {}", path.display()))?;
+    let mut save = CharacterSave::parse(&original)
+        .with_context(|| format!("could not parse character from {}", path.display()))?;
+
+    for (skill, xp) in set_xp {
+        save.set_skill_xp(*skill, *xp)?;
+    }
+    for (item_id, amount) in add_bank {
+        let item_id = item_definition::bank_item_id(items_path, *item_id)?;
+        let slot = save.add_bank_item(item_id, *amount)?;
+        log::info!("added {amount} of item {item_id} to bank slot {slot}");
+    }
+    if let Some((x, y, height)) = position {
+        save.set_position(Position { x, y, height });
+    }
+
+    let change = PendingChange {
+        path,
+        original,
+        modified: save.to_save_string(),
+    };
+    if change.is_noop() {
+        log::info!("{} is unchanged", change.path.display());
+    } else if dry_run {
+        print!("{}", change.unified_diff());
+    } else {
+        change.write()?;
+        log::info!("saved {}", change.path.display());
+    }
+    Ok(())
+}
+
+fn print_economy(
+    format: &ReportFormat,
+    characters_dir: &Path,
+    items_path: &Path,
+    top: usize,
+) -> Result<()> {
+    let items: HashMap<i32, ItemDefinition> = load_items(items_path)?
+        .into_iter()
+        .map(|item| (item.id, item))
+        .collect();
+    let valuation = Valuation::new(&items);
+
+    let paths = characters::save_paths(characters_dir)?;
+    log::info!("reading {} characters...", paths.len());
+    let pb = ProgressBar::new(paths.len().try_into().unwrap());
+    let holdings = paths
+        .par_iter()
+        .filter_map(|(name, path)| {
+            let holdings = match characters::load(path) {
+                Ok(save) => Some(economy::character_holdings(name, &save, &valuation)),
+                Err(e) => {
+                    log::warn!("skipping {name}: {e:#}");
+                    None
+                }
+            };
+            pb.inc(1);
+            holdings
+        })
+        .collect::<Vec<_>>();
+    pb.finish_and_clear();
+
+    let report = economy::report(&holdings, &valuation, top);
+    let s = match format {
+        ReportFormat::Basic => {
+            let mut lines = vec![
+                format!(
+                    "{} characters hold {} coins of wealth",
+                    report.characters, report.total_wealth
+                ),
+                format!(
+                    "  unnoted items: {}, noted items: {}",
+                    report.unnoted_wealth, report.noted_wealth
+                ),
+                "top holders:".to_string(),
+            ];
+            lines.extend(report.top_holders.iter().map(|wealth| {
+                format!(
+                    "  {}: {} (inventory {}, equipment {}, bank {})",
+                    wealth.name, wealth.total, wealth.inventory, wealth.equipment, wealth.bank
+                )
+            }));
+            lines.push("most valuable items in circulation:".to_string());
+            lines.extend(report.items.iter().take(top).map(|item| {
+                format!(
+                    "  {} {}: {} unnoted, {} noted worth {} held by {} characters, most by {} ({})",
+                    item.item_id,
+                    item.name.as_deref().unwrap_or(""),
+                    item.amounts.unnoted,
+                    item.amounts.noted,
+                    item.value,
+                    item.holders,
+                    item.largest_holder,
+                    item.largest_amount
+                )
+            }));
+            lines.join("\n")
+        }
+        ReportFormat::Json => serde_json::to_string(&report)?,
+    };
+
+    println!("{s}");
+    Ok(())
+}
+
+#[derive(Serialize, Debug)]
+struct ItemMigrationReport<'a> {
+    mapping: &'a ItemMapping,
+    files: &'a [FileMigration],
+}
+
+fn migrate_item(
+    from_to: Option<(i32, i32)>,
+    mapping_path: Option<&Path>,
+    data_dir: &Path,
+    item_ids_dir: &Path,
+    report_path: &Path,
+    dry_run: bool,
+) -> Result<()> {
+    let mut mapping = match mapping_path {
+        Some(path) => item_migration::load_mapping(path)?,
+        None => ItemMapping::new(),
+    };
+    mapping.extend(from_to);
+    if mapping.is_empty() {
+        anyhow::bail!("no items to migrate, specify --from and --to or --mapping-path");
+    }
+
+    let paths = DataPaths::new(data_dir);
+    let items: HashMap<i32, ItemDefinition> = load_items(&paths.item_definitions)?
+        .into_iter()
+        .map(|item| (item.id, item))
+        .collect();
+    let mapping = item_migration::with_noted_counterparts(&mapping, &items)?;
+    for (from, to) in &mapping {
+        log::info!("replacing item {from} with {to}");
+    }
+
+    log::info!("migrating files...");
+    let characters_dir = data_dir.join("characters");
+    let mut files = if characters_dir.is_dir() {
+        item_migration::migrate_characters(&characters_dir, &mapping)?
+    } else {
+        log::warn!(
+            "{} does not exist, skipping characters",
+            characters_dir.display()
+        );
+        Vec::new()
+    };
+    files.extend(item_migration::migrate_npc_drops(
+        &paths.npc_drops,
+        &mapping,
+    )?);
+    files.extend(item_migration::migrate_shops(&paths.shops, &mapping)?);
+    files.extend(item_migration::migrate_global_drops(
+        &paths.global_drops,
+        &mapping,
+    )?);
+    files.extend(item_migration::migrate_item_id_lists(
+        item_ids_dir,
+        &mapping,
+        &items,
+    )?);
+
+    let report = ItemMigrationReport {
+        mapping: &mapping,
+        files: &files,
+    };
+    let slots: usize = files.iter().map(|file| file.touches.len()).sum();
+    if dry_run {
+        for file in &files {
+            print!("{}", file.change.unified_diff());
+        }
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        log::info!("{slots} slots in {} files would be changed", files.len());
+        return Ok(());
+    }
+
+    // the report is written first so no file is migrated without a record of the change
+    fs::write(report_path, serde_json::to_string_pretty(&report)?)
+        .with_context(|| format!("could not write report to {}", report_path.display()))?;
+    let changes = files.iter().map(|file| file.change.clone()).collect_vec();
+    if let Err(e) = modify::write_all(&changes) {
+        if let Err(remove_error) = fs::remove_file(report_path) {
+            log::error!("could not remove {}: {remove_error}", report_path.display());
+        }
+        return Err(e);
+    }
+    log::info!(
+        "{slots} slots in {} files changed, see {}",
+        files.len(),
+        report_path.display()
+    );
+    Ok(())
+}
+
+#[derive(Serialize, Debug)]
+struct ModerationListReport<'a> {
+    list: ModerationList,
+    path: &'a Path,
+    entries: &'a [String],
+    rules: Vec<&'a Rule>,
+}
+
+fn list_moderation(
+    format: &ReportFormat,
+    data_dir: &Path,
+    list: Option<ModerationList>,
+) -> Result<()> {
+    let lists = match list {
+        Some(list) => vec![list],
+        None => ModerationList::ALL.to_vec(),
+    };
+    let files = lists
+        .into_iter()
+        .map(|list| ListFile::load(data_dir, list))
+        .collect::<Result<Vec<_>>>()?;
+    let rules = moderation::load_rules(data_dir)?;
+    let reports = files
+        .iter()
+        .map(|file| ModerationListReport {
+            list: file.list,
+            path: &file.path,
+            entries: &file.entries,
+            rules: rules.iter().filter(|rule| rule.list == file.list).collect(),
+        })
+        .collect_vec();
+
+    let s = match format {
+        ReportFormat::Basic => {
+            let now = SystemTime::now();
+            let mut lines = Vec::new();
+            for report in &reports {
+                lines.push(format!(
+                    "{}: {} entries in {}",
+                    report.list.name(),
+                    report.entries.len(),
+                    report.path.display()
+                ));
+                let mut covered = HashSet::new();
+                for rule in &report.rules {
+                    covered.extend(report.list.expand(&rule.rule)?);
+                    let expiry = match rule.expiry()? {
+                        None => "never expires".to_string(),
+                        Some(expiry) => match expiry.duration_since(now) {
+                            Ok(left) => format!(
+                                "expires in {}",
+                                humantime::format_duration(Duration::from_secs(left.as_secs()))
+                            ),
+                            Err(_) => "expired".to_string(),
+                        },
+                    };
+                    let reason = rule
+                        .reason
+                        .as_ref()
+                        .map(|reason| format!(": {reason}"))
+                        .unwrap_or_default();
+                    lines.push(format!(
+                        "  {} added {}, {expiry}{reason}",
+                        rule.rule, rule.added
+                    ));
                 }
+                lines.extend(
+                    report
+                        .entries
+                        .iter()
+                        .filter(|entry| !covered.contains(*entry))
+                        .map(|entry| format!("  {entry}")),
+                );
             }
-            // match against ids from id jsons
-            if desired_ids.contains(&definition.id) {
-                items.insert(definition.clone());
+            lines.join("\n")
+        }
+        ReportFormat::Json => serde_json::to_string(&reports)?,
+    };
+
+    println!("{s}");
+    Ok(())
+}
+
+fn add_moderation(
+    data_dir: &Path,
+    list: ModerationList,
+    entries: &[String],
+    duration: Option<Duration>,
+    reason: Option<&str>,
+) -> Result<()> {
+    let mut file = ListFile::load(data_dir, list)?;
+    let mut rules = moderation::load_rules(data_dir)?;
+    let now = SystemTime::now();
+
+    for entry in entries {
+        let rule = Rule {
+            list,
+            rule: list.normalize_rule(entry)?,
+            added: moderation::timestamp(now),
+            expires: duration.map(|duration| moderation::timestamp(now + duration)),
+            reason: reason.map(str::to_string),
+            entries: Vec::new(),
+        };
+        let name = rule.rule.clone();
+        let added = moderation::add_rule(&mut file, &mut rules, rule)?;
+        log::info!("{name}: {added} entries added to {}", list.name());
+    }
+
+    file.save()?;
+    moderation::save_rules(data_dir, &rules)
+}
+
+fn remove_moderation(data_dir: &Path, list: ModerationList, entries: &[String]) -> Result<()> {
+    let mut file = ListFile::load(data_dir, list)?;
+    let mut rules = moderation::load_rules(data_dir)?;
+
+    for entry in entries {
+        let rule = list.normalize_rule(entry)?;
+        // a rule only removes the entries it added, entries without a rule are removed directly
+        let Some(index) = rules.iter().position(|r| r.list == list && r.rule == rule) else {
+            let removed = list
+                .expand(&rule)?
+                .iter()
+                .filter(|entry| file.remove(entry))
+                .count();
+            if removed == 0 {
+                log::warn!("{rule} is not in {}", list.name());
+            } else {
+                log::info!("{rule}: {removed} entries removed from {}", list.name());
             }
+            continue;
+        };
+        let removed_rule = rules.remove(index);
+        let removed = moderation::release_rule(&mut file, &removed_rule, &mut rules)?;
+        let kept = list
+            .expand(&rule)?
+            .iter()
+            .filter(|entry| file.contains(entry))
+            .count();
+        log::info!("{rule}: {removed} entries removed from {}", list.name());
+        if kept > 0 {
+            log::warn!(
+                "{rule}: {kept} entries were listed before it or are covered by another rule and stay listed"
+            );
         }
-        pb.inc(1);
     }
 
-    Ok(items)
+    file.save()?;
+    moderation::save_rules(data_dir, &rules)
 }
 
-fn debug() -> Result<()> {
+fn expire_moderation(data_dir: &Path, dry_run: bool) -> Result<()> {
+    let rules = moderation::load_rules(data_dir)?;
+    let now = SystemTime::now();
+    let mut expired = Vec::new();
+    let mut live = Vec::new();
+    for rule in rules {
+        if rule.is_expired(now)? {
+            expired.push(rule);
+        } else {
+            live.push(rule);
+        }
+    }
+    if expired.is_empty() {
+        log::info!("no entries have expired");
+        return Ok(());
+    }
+
+    for list in ModerationList::ALL {
+        let list_expired = expired
+            .iter()
+            .filter(|rule| rule.list == list)
+            .collect_vec();
+        if list_expired.is_empty() {
+            continue;
+        }
+
+        // the entries are removed from the loaded file even on a dry run, which is never saved
+        let mut file = ListFile::load(data_dir, list)?;
+        for rule in list_expired {
+            let removed = moderation::release_rule(&mut file, rule, &mut live)?;
+            let expires = rule.expires.as_deref().unwrap_or_default();
+            if dry_run {
+                println!(
+                    "{} {} expired {expires}, {removed} entries would be removed",
+                    list.name(),
+                    rule.rule
+                );
+            } else {
+                log::info!(
+                    "{} {} expired {expires}, {removed} entries removed",
+                    list.name(),
+                    rule.rule
+                );
+            }
+        }
+        if !dry_run {
+            file.save()?;
+        }
+    }
+
+    if !dry_run {
+        moderation::save_rules(data_dir, &live)?;
+    }
+    Ok(())
+}
+
+#[derive(Serialize, Debug, Default)]
+struct RehashReport {
+    saves: usize,
+    hashed: Vec<String>,
+    already_hashed: usize,
+    missing_password: Vec<String>,
+    unreadable: Vec<String>,
+    backup_dir: Option<PathBuf>,
+}
+
+/// copies the original saves to a new subdirectory of the backup directory,
+/// then writes the changes and returns the subdirectory
+fn write_with_backup(changes: &[PendingChange], backup_dir: &Path, run: &str) -> Result<PathBuf> {
+    let unix_time = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)?
+        .as_secs();
+    fs::create_dir_all(backup_dir)
+        .with_context(|| format!("could not create {}", backup_dir.display()))?;
+    // runs in the same second get a numbered directory rather than sharing one
+    let mut number = 1;
+    let run_dir = loop {
+        let dir_name = match number {
+            1 => format!("{run}-{unix_time}"),
+            _ => format!("{run}-{unix_time}-{number}"),
+        };
+        let run_dir = backup_dir.join(dir_name);
+        match fs::create_dir(&run_dir) {
+            Ok(()) => break run_dir,
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => number += 1,
+            Err(e) => {
+                return Err(e).with_context(|| format!("could not create {}", run_dir.display()))
+            }
+        }
+    };
+    for change in changes {
+        let file_name = change
+            .path
+            .file_name()
+            .context("save without a file name")?;
+        fs::write(run_dir.join(file_name), &change.original)
+            .with_context(|| format!("could not back up {}", change.path.display()))?;
+    }
+    modify::write_all(changes)?;
+    Ok(run_dir)
+}
+
+fn rehash_accounts(
+    format: &ReportFormat,
+    characters_dir: &Path,
+    backup_dir: &Path,
+    dry_run: bool,
+) -> Result<()> {
+    let paths = characters::save_paths(characters_dir)?;
+    let mut report = RehashReport {
+        saves: paths.len(),
+        ..Default::default()
+    };
+
+    let mut changes = Vec::new();
+    for (name, path) in paths {
+        let original = fs::read_to_string(&path)
+            .with_context(|| format!("could not read character from {}", path.display()))?;
+        let mut save = match CharacterSave::parse(&original) {
+            Ok(save) => save,
+            Err(e) => {
+                log::warn!("skipping {name}: {e:#}");
+                report.unreadable.push(name);
+                continue;
+            }
+        };
+        match accounts::rehash(&mut save) {
+            RehashOutcome::Hashed => {
+                report.hashed.push(name);
+                changes.push(PendingChange {
+                    path,
+                    original,
+                    modified: save.to_save_string(),
+                });
+            }
+            RehashOutcome::AlreadyHashed => report.already_hashed += 1,
+            RehashOutcome::MissingPassword => report.missing_password.push(name),
+        }
+    }
+
+    if !dry_run && !changes.is_empty() {
+        report.backup_dir = Some(write_with_backup(&changes, backup_dir, "rehash")?);
+    }
+
+    let s = match format {
+        ReportFormat::Basic => {
+            let verb = if dry_run { "would be hashed" } else { "hashed" };
+            let mut lines = vec![
+                format!("{} saves read", report.saves),
+                format!("{} plaintext passwords {verb}", report.hashed.len()),
+                format!("{} passwords already hashed", report.already_hashed),
+            ];
+            if !report.missing_password.is_empty() {
+                lines.push(format!(
+                    "{} saves have no password and accept any password: {}",
+                    report.missing_password.len(),
+                    report.missing_password.join(", ")
+                ));
+            }
+            if !report.unreadable.is_empty() {
+                lines.push(format!(
+                    "{} saves could not be parsed: {}",
+                    report.unreadable.len(),
+                    report.unreadable.join(", ")
+                ));
+            }
+            if let Some(backup_dir) = &report.backup_dir {
+                lines.push(format!("originals backed up to {}", backup_dir.display()));
+            }
+            lines.join("\n")
+        }
+        ReportFormat::Json => serde_json::to_string(&report)?,
+    };
+
+    println!("{s}");
+    Ok(())
+}
+
+fn write_highscores(
+    characters_dir: &Path,
+    items_path: &Path,
+    output_dir: &Path,
+    top: usize,
+) -> Result<()> {
+    let items: HashMap<i32, ItemDefinition> = load_items(items_path)?
+        .into_iter()
+        .map(|item| (item.id, item))
+        .collect();
+    let valuation = Valuation::new(&items);
+
+    let paths = characters::save_paths(characters_dir)?;
+    log::info!("reading {} characters...", paths.len());
+    let pb = ProgressBar::new(paths.len().try_into().unwrap());
+    let players = paths
+        .par_iter()
+        .filter_map(|(name, path)| {
+            // HighscoresHandler names players after the file name up to the first dot
+            let name = name.split('.').next().unwrap_or_default();
+            let stats = characters::load(path).and_then(|save| {
+                if !highscores::is_ranked(name, &save)? {
+                    return Ok(None);
+                }
+                highscores::player_stats(name, &save, &valuation).map(Some)
+            });
+            pb.inc(1);
+            stats.unwrap_or_else(|e| {
+                log::warn!("skipping {name}: {e:#}");
+                None
+            })
+        })
+        .collect::<Vec<_>>();
+    pb.finish_and_clear();
+
+    let generated = moderation::timestamp(SystemTime::now());
+    let highscores = highscores::rank(&players, generated, top);
+    fs::create_dir_all(output_dir)
+        .with_context(|| format!("could not create {}", output_dir.display()))?;
+    let json_path = output_dir.join("highscores.json");
+    fs::write(&json_path, serde_json::to_string_pretty(&highscores)?)
+        .with_context(|| format!("could not write {}", json_path.display()))?;
+    let html_path = output_dir.join("index.html");
+    fs::write(&html_path, highscores::to_html(&highscores))
+        .with_context(|| format!("could not write {}", html_path.display()))?;
+    log::info!(
+        "ranked {} players in {}",
+        highscores.players,
+        output_dir.display()
+    );
+    Ok(())
+}
+
+#[derive(Serialize, Debug, Default)]
+struct CheckReport {
+    saves: usize,
+    /// the problems left in the saves
+    problems: Vec<save_check::Problem>,
+    /// the problems which were repaired, with what was done about them
+    repaired_problems: Vec<save_check::Problem>,
+    /// the saves which were repaired
+    repaired: Vec<String>,
+    backup_dir: Option<PathBuf>,
+}
+
+fn check_accounts(
+    format: &ReportFormat,
+    characters_dir: &Path,
+    items_path: &Path,
+    repair: bool,
+    backup_dir: &Path,
+) -> Result<()> {
+    let items: HashMap<i32, ItemDefinition> = load_items(items_path)?
+        .into_iter()
+        .map(|item| (item.id, item))
+        .collect();
+    let paths = characters::save_paths(characters_dir)?;
+    let mut report = CheckReport {
+        saves: paths.len(),
+        ..Default::default()
+    };
+
+    let mut changes = Vec::new();
+    for (name, path) in paths {
+        let original = fs::read_to_string(&path)
+            .with_context(|| format!("could not read character from {}", path.display()))?;
+        let mut checked = save_check::check(&path, &original, &items);
+        if repair && !checked.fixes.is_empty() {
+            let modified = checked.repair(&original);
+            match CharacterSave::parse(&modified) {
+                Ok(_) => {
+                    let (repaired, left): (Vec<_>, Vec<_>) = checked
+                        .problems
+                        .into_iter()
+                        .partition(|problem| problem.repair.is_some());
+                    checked.problems = left;
+                    report.repaired_problems.extend(repaired);
+                    report.repaired.push(name);
+                    changes.push(PendingChange {
+                        path,
+                        original,
+                        modified,
+                    });
+                }
+                Err(e) => log::warn!("not repairing {name}, the repaired save is invalid: {e:#}"),
+            }
+        }
+        report.problems.extend(checked.problems);
+    }
+
+    if !changes.is_empty() {
+        report.backup_dir = Some(write_with_backup(&changes, backup_dir, "check")?);
+    }
+
+    let s = match format {
+        ReportFormat::Basic => {
+            let mut lines = report
+                .repaired_problems
+                .iter()
+                .map(|problem| match &problem.repair {
+                    Some(repair) => format!("{problem} (repaired, {repair})"),
+                    None => problem.to_string(),
+                })
+                .collect_vec();
+            lines.extend(report.problems.iter().map(|problem| match &problem.repair {
+                Some(_) => format!("{problem} (repairable)"),
+                None => problem.to_string(),
+            }));
+            lines.push(format!(
+                "{} saves read, {} problems repaired, {} problems left",
+                report.saves,
+                report.repaired_problems.len(),
+                report.problems.len()
+            ));
+            if !report.repaired.is_empty() {
+                lines.push(format!(
+                    "{} saves repaired: {}",
+                    report.repaired.len(),
+                    report.repaired.join(", ")
+                ));
+            }
+            if let Some(backup_dir) = &report.backup_dir {
+                lines.push(format!("originals backed up to {}", backup_dir.display()));
+            }
+            lines.join("\n")
+        }
+        ReportFormat::Json => serde_json::to_string(&report)?,
+    };
+    println!("{s}");
+
+    if !report.problems.is_empty() {
+        anyhow::bail!("{} problems left unrepaired", report.problems.len());
+    }
+    Ok(())
+}
+
+#[derive(Serialize, Debug, Default)]
+struct BulkReport {
+    saves: usize,
+    selected: usize,
+    journal: Vec<JournalEntry>,
+    unreadable: Vec<String>,
+    backup_dir: Option<PathBuf>,
+}
+
+fn bulk_accounts(
+    format: &ReportFormat,
+    characters_dir: &Path,
+    selector: &Selector,
+    actions: &[Action],
+    backup_dir: &Path,
+    dry_run: bool,
+) -> Result<()> {
+    if actions.is_empty() {
+        anyhow::bail!("no actions were specified");
+    }
+    let paths = characters::save_paths(characters_dir)?;
+    let mut report = BulkReport {
+        saves: paths.len(),
+        ..Default::default()
+    };
+
+    let mut changes = Vec::new();
+    for (name, path) in paths {
+        let original = fs::read_to_string(&path)
+            .with_context(|| format!("could not read character from {}", path.display()))?;
+        let save = CharacterSave::parse(&original).and_then(|save| {
+            let selected = selector.matches(&name, &save)?;
+            Ok((save, selected))
+        });
+        let mut save = match save {
+            Ok((save, true)) => save,
+            Ok((_, false)) => continue,
+            Err(e) => {
+                log::warn!("skipping {name}: {e:#}");
+                report.unreadable.push(name);
+                continue;
+            }
+        };
+        report.selected += 1;
+        let entry = bulk::apply(&name, &path, &mut save, actions)
+            .with_context(|| format!("could not apply the actions to {name}"))?;
+        if let Some(entry) = entry {
+            report.journal.push(entry);
+            changes.push(PendingChange {
+                path,
+                original,
+                modified: save.to_save_string(),
+            });
+        }
+    }
+
+    if !dry_run && !changes.is_empty() {
+        let run_dir = write_with_backup(&changes, backup_dir, "bulk")?;
+        let journal_path = run_dir.join("journal.json");
+        fs::write(
+            &journal_path,
+            serde_json::to_string_pretty(&report.journal)?,
+        )
+        .with_context(|| format!("could not write {}", journal_path.display()))?;
+        report.backup_dir = Some(run_dir);
+    }
+
+    let s = match format {
+        ReportFormat::Basic => {
+            let mut lines = report
+                .journal
+                .iter()
+                .map(|entry| format!("{}: {}", entry.name, entry.changes.join(", ")))
+                .collect_vec();
+            let verb = if dry_run {
+                "would be changed"
+            } else {
+                "changed"
+            };
+            lines.push(format!(
+                "{} saves read, {} selected, {} {verb}",
+                report.saves,
+                report.selected,
+                report.journal.len()
+            ));
+            if !report.unreadable.is_empty() {
+                lines.push(format!(
+                    "{} saves could not be parsed: {}",
+                    report.unreadable.len(),
+                    report.unreadable.join(", ")
+                ));
+            }
+            if let Some(backup_dir) = &report.backup_dir {
+                lines.push(format!(
+                    "originals and journal written to {}",
+                    backup_dir.display()
+                ));
+            }
+            lines.join("\n")
+        }
+        ReportFormat::Json => serde_json::to_string(&report)?,
+    };
+
+    println!("{s}");
     Ok(())
 }
+
+fn anonymize_accounts(
+    characters_dir: &Path,
+    output_dir: &Path,
+    pseudonyms_path: &Path,
+    names: &[String],
+) -> Result<()> {
+    let mut pseudonyms = Pseudonyms::load(pseudonyms_path)?;
+    let mut anonymized = Vec::with_capacity(names.len());
+    for name in names {
+        let mut save = characters::load(&characters::save_path(characters_dir, name))?;
+        let pseudonym = anonymize::anonymize(name, &mut save, &mut pseudonyms);
+        anonymized.push((name, pseudonym, save));
+    }
+
+    fs::create_dir_all(output_dir)
+        .with_context(|| format!("could not create {}", output_dir.display()))?;
+    for (name, pseudonym, save) in anonymized {
+        let path = characters::save_path(output_dir, &pseudonym);
+        fs::write(&path, save.to_save_string())
+            .with_context(|| format!("could not write {}", path.display()))?;
+        println!("{name} -> {pseudonym}: {}", path.display());
+    }
+    pseudonyms.save(pseudonyms_path)
+}
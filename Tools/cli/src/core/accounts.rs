@@ -0,0 +1,42 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use super::characters::CharacterSave;
+
+/// mirrors `PlayerSave.passwordHash`, the base64 SHA-256 of the base64 MD5 of the password
+pub fn password_hash(password: &str) -> String {
+    let md5 = STANDARD.encode(md5::compute(password.as_bytes()).0);
+    STANDARD.encode(Sha256::digest(md5.as_bytes()))
+}
+
+/// whether the saved password is `PlayerSave.passwordHash` output rather than plaintext
+pub fn is_password_hash(password: &str) -> bool {
+    password.len() == 44
+        && STANDARD
+            .decode(password)
+            .is_ok_and(|digest| digest.len() == 32)
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum RehashOutcome {
+    /// the plaintext password was replaced with its hash
+    Hashed,
+    AlreadyHashed,
+    /// the save has no password, the server accepts any password for it
+    MissingPassword,
+}
+
+/// replaces a plaintext password with the hash the server verifies at login
+pub fn rehash(save: &mut CharacterSave) -> RehashOutcome {
+    let Some(password) = save.password() else {
+        return RehashOutcome::MissingPassword;
+    };
+    if is_password_hash(password) {
+        return RehashOutcome::AlreadyHashed;
+    }
+    let hash = password_hash(password);
+    save.set_password(&hash);
+    RehashOutcome::Hashed
+}
@@ -0,0 +1,97 @@
+use std::{collections::BTreeMap, path::Path};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::characters::{self, CharacterSave, Contact};
+
+/// written to `last-ip` and `creationAddress`, which `PlayerSave.loadGame` reads as plain strings
+pub const PLACEHOLDER_IP: &str = "127.0.0.1";
+/// the `discord-user-id` `PlayerSave.saveGame` writes for an account without a linked discord user
+pub const NO_DISCORD_USER: &str = "null";
+/// pseudonyms are this prefix followed by a number, which `Misc.playerNameToInt64` can encode
+pub const PSEUDONYM_PREFIX: &str = "anon";
+
+/// the `[CHARACTER]` keys of the bank pin and the values of an account without one
+const BANK_PIN: [(&str, &str); 6] = [
+    ("bankPin1", "0"),
+    ("bankPin2", "0"),
+    ("bankPin3", "0"),
+    ("bankPin4", "0"),
+    ("hasBankpin", "false"),
+    ("setPin", "false"),
+];
+
+/// real names mapped to their pseudonyms, kept between runs so a name always gets the
+/// same pseudonym, whether it is the account or a friend of another account
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(transparent)]
+pub struct Pseudonyms(BTreeMap<String, String>);
+
+impl Pseudonyms {
+    pub fn load(path: &Path) -> Result<Pseudonyms> {
+        if !path.exists() {
+            return Ok(Pseudonyms::default());
+        }
+        let contents = std::fs::read_to_string(path)
+            .with_context(|| format!("could not read {}", path.display()))?;
+        serde_json::from_str(&contents)
+            .with_context(|| format!("could not parse {}", path.display()))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let mut json = serde_json::to_string_pretty(self)?;
+        json.push('\n');
+        std::fs::write(path, json).with_context(|| format!("could not write {}", path.display()))
+    }
+
+    /// the pseudonym of the name, names are compared the way the server encodes them
+    pub fn pseudonym(&mut self, name: &str) -> String {
+        self.pseudonym_for_hash(characters::encode_name(name))
+    }
+
+    fn pseudonym_for_hash(&mut self, name_hash: i64) -> String {
+        let name = characters::decode_name(name_hash);
+        if let Some(pseudonym) = self.0.get(&name) {
+            return pseudonym.clone();
+        }
+        let pseudonym = (self.0.len() + 1..)
+            .map(|number| format!("{PSEUDONYM_PREFIX}{number}"))
+            .find(|pseudonym| !self.0.values().any(|used| used == pseudonym))
+            .unwrap_or_default();
+        self.0.insert(name, pseudonym.clone());
+        pseudonym
+    }
+}
+
+/// replaces the username, friends and ignores with pseudonyms, removes the password so any
+/// password logs in, and clears the ip addresses, bank pin and discord user, returning the
+/// pseudonym
+pub fn anonymize(name: &str, save: &mut CharacterSave, pseudonyms: &mut Pseudonyms) -> String {
+    let pseudonym = pseudonyms.pseudonym(name);
+    save.set_account_property("character-username", &pseudonym);
+    save.remove_account_property("character-password");
+    for key in ["last-ip", "creationAddress"] {
+        if save.property(key).is_some() {
+            save.set_property(key, PLACEHOLDER_IP);
+        }
+    }
+    if save.property("discord-user-id").is_some() {
+        save.set_property("discord-user-id", NO_DISCORD_USER);
+    }
+    for (key, value) in BANK_PIN {
+        if save.property(key).is_some() {
+            save.set_property(key, value);
+        }
+    }
+
+    let mut pseudonymize = |contacts: &mut Vec<Contact>| {
+        for contact in contacts {
+            let pseudonym = pseudonyms.pseudonym_for_hash(contact.name_hash);
+            contact.name_hash = characters::encode_name(&pseudonym);
+        }
+    };
+    pseudonymize(&mut save.friends);
+    pseudonymize(&mut save.ignores);
+    pseudonym
+}
@@ -0,0 +1,116 @@
+use std::io::Read;
+
+use anyhow::{Context, Result};
+use bzip2::read::BzDecoder;
+
+use super::buffer::Buffer;
+
+/// the file in the archive index containing the config archive
+pub const CONFIG_ARCHIVE: usize = 2;
+
+/// a named file inside an [`Archive`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ArchiveEntry {
+    /// the [`hash`] of the entry's name
+    pub identifier: i32,
+    pub data: Vec<u8>,
+}
+
+/// a JAG archive as stored in the archive index, mirrors `org.apollo.cache.archive.Archive`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Archive {
+    pub entries: Vec<ArchiveEntry>,
+}
+
+/// the hash of an entry name, see `Archive.hash`
+pub fn hash(name: &str) -> i32 {
+    name.to_uppercase().chars().fold(0i32, |hash, character| {
+        hash.wrapping_mul(61)
+            .wrapping_add(character as i32)
+            .wrapping_sub(32)
+    })
+}
+
+/// decompresses headerless bzip2 data, see `CompressionUtil.debzip2`
+pub fn debzip2(compressed: &[u8], decompressed_size: usize) -> Result<Vec<u8>> {
+    let stream = b"BZh1"
+        .iter()
+        .chain(compressed)
+        .copied()
+        .collect::<Vec<_>>();
+    let mut decompressed = vec![0; decompressed_size];
+    BzDecoder::new(stream.as_slice())
+        .read_exact(&mut decompressed)
+        .context("could not decompress bzip2 data")?;
+    Ok(decompressed)
+}
+
+impl Archive {
+    /// decodes an archive which is either compressed as a whole or entry by entry
+    pub fn decode(data: &[u8]) -> Result<Self> {
+        let mut buffer = Buffer::new(data);
+        let extracted_size = buffer.read_u24()? as usize;
+        let size = buffer.read_u24()? as usize;
+
+        let whole;
+        let extracted = size != extracted_size;
+        if extracted {
+            whole = debzip2(buffer.read_bytes(size)?, extracted_size)?;
+            buffer = Buffer::new(&whole);
+        }
+
+        let entry_count = buffer.read_u16()?;
+        let mut headers = Vec::with_capacity(entry_count.into());
+        for _ in 0..entry_count {
+            let identifier = buffer.read_i32()?;
+            let extracted_size = buffer.read_u24()? as usize;
+            let size = buffer.read_u24()? as usize;
+            headers.push((identifier, extracted_size, size));
+        }
+
+        let mut entries = Vec::with_capacity(headers.len());
+        for (identifier, extracted_size, size) in headers {
+            let data = if extracted {
+                buffer.read_bytes(extracted_size)?.to_vec()
+            } else {
+                debzip2(buffer.read_bytes(size)?, extracted_size)?
+            };
+            entries.push(ArchiveEntry { identifier, data });
+        }
+        Ok(Self { entries })
+    }
+
+    pub fn entry(&self, name: &str) -> Result<&[u8]> {
+        let identifier = hash(name);
+        self.entries
+            .iter()
+            .find(|entry| entry.identifier == identifier)
+            .map(|entry| entry.data.as_slice())
+            .with_context(|| format!("could not find archive entry {name}"))
+    }
+
+    /// decodes every definition of a `{name}.dat` entry, whose offsets are stored in `{name}.idx`
+    /// as a count followed by the size of each definition
+    pub fn decode_definitions<T>(
+        &self,
+        name: &str,
+        mut decode: impl FnMut(i32, &mut Buffer) -> Result<T>,
+    ) -> Result<Vec<T>> {
+        let data = self.entry(&format!("{name}.dat"))?;
+        let mut idx = Buffer::new(self.entry(&format!("{name}.idx"))?);
+
+        let count = idx.read_u16()?;
+        // the data starts with the count as well
+        let mut offset = 2;
+        let mut definitions = Vec::with_capacity(count.into());
+        for id in 0..i32::from(count) {
+            let mut buffer = Buffer::new(data);
+            buffer.set_position(offset);
+            let definition = decode(id, &mut buffer)
+                .with_context(|| format!("could not decode {name} definition {id}"))?;
+            definitions.push(definition);
+            offset += usize::from(idx.read_u16()?);
+        }
+        Ok(definitions)
+    }
+}
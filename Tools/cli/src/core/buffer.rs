@@ -0,0 +1,96 @@
+use anyhow::{bail, Result};
+
+/// the byte which ends strings in the cache, see `BufferUtil.STRING_TERMINATOR`
+pub const STRING_TERMINATOR: u8 = 10;
+
+/// reads big endian values from cache data, failing instead of panicking at the end of the data
+#[derive(Clone, Debug)]
+pub struct Buffer<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> Buffer<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, position: 0 }
+    }
+
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn set_position(&mut self, position: usize) {
+        self.position = position;
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.data.len().saturating_sub(self.position)
+    }
+
+    pub fn read_bytes(&mut self, len: usize) -> Result<&'a [u8]> {
+        let Some(bytes) = self.data.get(self.position..self.position + len) else {
+            bail!(
+                "could not read {len} bytes at position {} of {}",
+                self.position,
+                self.data.len()
+            );
+        };
+        self.position += len;
+        Ok(bytes)
+    }
+
+    fn read_array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let mut array = [0; N];
+        array.copy_from_slice(self.read_bytes(N)?);
+        Ok(array)
+    }
+
+    pub fn read_u8(&mut self) -> Result<u8> {
+        Ok(self.read_array::<1>()?[0])
+    }
+
+    pub fn read_i8(&mut self) -> Result<i8> {
+        Ok(self.read_u8()? as i8)
+    }
+
+    pub fn read_u16(&mut self) -> Result<u16> {
+        Ok(u16::from_be_bytes(self.read_array()?))
+    }
+
+    pub fn read_i16(&mut self) -> Result<i16> {
+        Ok(i16::from_be_bytes(self.read_array()?))
+    }
+
+    /// `BufferUtil.readUnsignedMedium`
+    pub fn read_u24(&mut self) -> Result<u32> {
+        let [a, b, c] = self.read_array()?;
+        Ok(u32::from_be_bytes([0, a, b, c]))
+    }
+
+    pub fn read_i32(&mut self) -> Result<i32> {
+        Ok(i32::from_be_bytes(self.read_array()?))
+    }
+
+    /// `BufferUtil.readSmart`, one byte for values below 128 and two bytes otherwise
+    pub fn read_smart(&mut self) -> Result<u16> {
+        let Some(peek) = self.data.get(self.position) else {
+            bail!("could not read a smart at position {}", self.position);
+        };
+        if *peek < 128 {
+            Ok(u16::from(self.read_u8()?))
+        } else {
+            Ok(self.read_u16()? - 0x8000)
+        }
+    }
+
+    /// reads a string ending in [`STRING_TERMINATOR`], each byte is one latin-1 character
+    pub fn read_string(&mut self) -> Result<String> {
+        let rest = &self.data[self.position.min(self.data.len())..];
+        let Some(len) = rest.iter().position(|byte| *byte == STRING_TERMINATOR) else {
+            bail!("unterminated string at position {}", self.position);
+        };
+        let string = rest[..len].iter().map(|byte| char::from(*byte)).collect();
+        self.position += len + 1;
+        Ok(string)
+    }
+}
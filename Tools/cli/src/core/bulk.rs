@@ -0,0 +1,151 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    characters::{CharacterSave, Position},
+    spawns::Area,
+};
+
+/// `Constants.RESPAWN_X` and `Constants.RESPAWN_Y`, used when the config does not set them
+pub const DEFAULT_RESPAWN: (i32, i32) = (3222, 3218);
+
+/// the keys of the server config read by `ConfigLoader.loadSettings` that rs-cli needs
+#[derive(Deserialize, Debug, Default)]
+struct ServerConfig {
+    respawn_x: Option<i32>,
+    respawn_y: Option<i32>,
+}
+
+/// the tile players are moved to when they die, on height 0
+pub fn respawn(config_path: &Path) -> Result<Position> {
+    let config = if config_path.exists() {
+        let contents = std::fs::read_to_string(config_path)
+            .with_context(|| format!("could not read {}", config_path.display()))?;
+        serde_json::from_str(&contents)
+            .with_context(|| format!("could not parse {}", config_path.display()))?
+    } else {
+        log::warn!(
+            "{} does not exist, using the default respawn",
+            config_path.display()
+        );
+        ServerConfig::default()
+    };
+    Ok(Position {
+        x: config.respawn_x.unwrap_or(DEFAULT_RESPAWN.0),
+        y: config.respawn_y.unwrap_or(DEFAULT_RESPAWN.1),
+        height: 0,
+    })
+}
+
+/// the saves an operation applies to, every criterion set must match
+#[derive(Clone, Debug, Default)]
+pub struct Selector {
+    /// matched against the save's file name
+    pub name: Option<Regex>,
+    pub rights: Option<i32>,
+    pub bot: Option<bool>,
+    /// matched against the position on any height
+    pub area: Option<Area>,
+    /// an item id held in the inventory, equipment or bank
+    pub owns: Option<i32>,
+}
+
+impl Selector {
+    pub fn matches(&self, name: &str, save: &CharacterSave) -> Result<bool> {
+        if self
+            .name
+            .as_ref()
+            .is_some_and(|regex| !regex.is_match(name))
+        {
+            return Ok(false);
+        }
+        if let Some(rights) = self.rights {
+            if save.rights()? != rights {
+                return Ok(false);
+            }
+        }
+        if self.bot.is_some_and(|bot| bot != save.is_bot()) {
+            return Ok(false);
+        }
+        if let Some(area) = &self.area {
+            let position = save.position()?;
+            if !area.contains(position.x, position.y) {
+                return Ok(false);
+            }
+        }
+        Ok(self.owns.is_none_or(|item_id| save.holds_item(item_id)))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Action {
+    /// adds the item to the bank
+    GiveItem {
+        item_id: i32,
+        amount: i32,
+    },
+    MovePlayer(Position),
+    /// removes a `[CHARACTER]` key, which the server then reads as 0
+    ClearKey(String),
+}
+
+impl Action {
+    /// applies the action and describes the change, `None` if the save is unchanged
+    pub fn apply(&self, save: &mut CharacterSave) -> Result<Option<String>> {
+        match self {
+            Action::GiveItem { item_id, amount } => {
+                let slot = save.add_bank_item(*item_id, *amount)?;
+                Ok(Some(format!(
+                    "added {amount} of item {item_id} to bank slot {slot}"
+                )))
+            }
+            Action::MovePlayer(position) => {
+                let from = save.position()?;
+                if from == *position {
+                    return Ok(None);
+                }
+                save.set_position(*position);
+                Ok(Some(format!(
+                    "moved from {},{},{} to {},{},{}",
+                    from.x, from.y, from.height, position.x, position.y, position.height
+                )))
+            }
+            Action::ClearKey(key) => {
+                let removed = save.remove_property(key);
+                if removed.is_empty() {
+                    return Ok(None);
+                }
+                Ok(Some(format!("cleared {key} = {}", removed.join(", "))))
+            }
+        }
+    }
+}
+
+/// the changes made to one save
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct JournalEntry {
+    pub name: String,
+    pub path: PathBuf,
+    pub changes: Vec<String>,
+}
+
+/// applies every action to the save, returning its journal entry if anything changed
+pub fn apply(
+    name: &str,
+    path: &Path,
+    save: &mut CharacterSave,
+    actions: &[Action],
+) -> Result<Option<JournalEntry>> {
+    let mut changes = Vec::new();
+    for action in actions {
+        changes.extend(action.apply(save)?);
+    }
+    Ok((!changes.is_empty()).then(|| JournalEntry {
+        name: name.to_string(),
+        path: path.to_path_buf(),
+        changes,
+    }))
+}
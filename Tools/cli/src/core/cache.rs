@@ -0,0 +1,264 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use super::archive::Archive;
+
+/// the size of an entry in an index file
+pub const INDEX_SIZE: usize = 6;
+/// the size of the header before each chunk in the data file
+pub const HEADER_SIZE: usize = 8;
+/// the maximum amount of file data in a sector
+pub const CHUNK_SIZE: usize = 512;
+/// the size of a sector in the data file
+pub const BLOCK_SIZE: usize = HEADER_SIZE + CHUNK_SIZE;
+/// the most index files a cache can have
+pub const MAX_INDICES: usize = 255;
+
+/// the index containing the archives such as the config and title archives
+pub const ARCHIVE_INDEX: usize = 0;
+pub const MODEL_INDEX: usize = 1;
+pub const ANIMATION_INDEX: usize = 2;
+pub const MIDI_INDEX: usize = 3;
+pub const MAP_INDEX: usize = 4;
+
+/// a problem with the structure of the cache, which the java `IndexedFileSystem` would throw for
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum IntegrityError {
+    /// there is no index file with this number
+    MissingIndex { index: usize },
+    /// the file id is past the end of the index file
+    MissingFile { index: usize, file: usize },
+    /// a sector of the chain lies outside of the data file
+    SectorOutOfRange {
+        index: usize,
+        file: usize,
+        chunk: usize,
+        sector: usize,
+    },
+    /// a sector header belongs to another file
+    FileIdMismatch {
+        index: usize,
+        file: usize,
+        chunk: usize,
+        found: usize,
+    },
+    /// a sector header belongs to another index, `found` is the raw index byte of the header
+    IndexMismatch {
+        index: usize,
+        file: usize,
+        chunk: usize,
+        found: usize,
+    },
+    /// the sectors of a file are out of order
+    ChunkMismatch {
+        index: usize,
+        file: usize,
+        chunk: usize,
+        found: usize,
+    },
+}
+
+impl std::fmt::Display for IntegrityError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IntegrityError::MissingIndex { index } => {
+                write!(f, "index {index} does not exist")
+            }
+            IntegrityError::MissingFile { index, file } => {
+                write!(f, "index {index} file {file}: not in the index")
+            }
+            IntegrityError::SectorOutOfRange {
+                index,
+                file,
+                chunk,
+                sector,
+            } => write!(
+                f,
+                "index {index} file {file}: chunk {chunk} points to sector {sector} outside of the data file"
+            ),
+            IntegrityError::FileIdMismatch {
+                index,
+                file,
+                chunk,
+                found,
+            } => write!(
+                f,
+                "index {index} file {file}: chunk {chunk} belongs to file {found}"
+            ),
+            IntegrityError::IndexMismatch {
+                index,
+                file,
+                chunk,
+                found,
+            } => write!(
+                f,
+                "index {index} file {file}: chunk {chunk} has index byte {found} instead of {}",
+                index + 1
+            ),
+            IntegrityError::ChunkMismatch {
+                index,
+                file,
+                chunk,
+                found,
+            } => write!(
+                f,
+                "index {index} file {file}: chunk {chunk} is labelled as chunk {found}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for IntegrityError {}
+
+/// the location of a file in the data file, see `org.apollo.cache.Index`
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IndexEntry {
+    /// the size of the file in bytes
+    pub size: usize,
+    /// the first sector of the file
+    pub sector: usize,
+}
+
+impl IndexEntry {
+    fn decode(bytes: &[u8]) -> Self {
+        Self {
+            size: read_u24(&bytes[0..3]),
+            sector: read_u24(&bytes[3..6]),
+        }
+    }
+}
+
+fn read_u24(bytes: &[u8]) -> usize {
+    (usize::from(bytes[0]) << 16) | (usize::from(bytes[1]) << 8) | usize::from(bytes[2])
+}
+
+/// read only access to `main_file_cache.dat` and its `main_file_cache.idx*` files,
+/// mirrors `org.apollo.cache.IndexedFileSystem`
+pub struct IndexedFileSystem {
+    indices: Vec<Option<Vec<u8>>>,
+    data: Vec<u8>,
+}
+
+impl IndexedFileSystem {
+    pub fn open(dir: &Path) -> Result<Self> {
+        let mut indices = Vec::new();
+        for index in 0..MAX_INDICES {
+            let path = dir.join(format!("main_file_cache.idx{index}"));
+            if path.is_file() {
+                let bytes = std::fs::read(&path)
+                    .with_context(|| format!("could not read {}", path.display()))?;
+                indices.resize(index + 1, None);
+                indices[index] = Some(bytes);
+            }
+        }
+        if indices.is_empty() {
+            anyhow::bail!("no index files found in {}", dir.display());
+        }
+
+        let path = dir.join("main_file_cache.dat");
+        let data =
+            std::fs::read(&path).with_context(|| format!("could not read {}", path.display()))?;
+        Ok(Self { indices, data })
+    }
+
+    /// the numbers of all index files which exist
+    pub fn indices(&self) -> Vec<usize> {
+        (0..self.indices.len())
+            .filter(|index| self.indices[*index].is_some())
+            .collect()
+    }
+
+    fn index_file(&self, index: usize) -> Result<&[u8], IntegrityError> {
+        self.indices
+            .get(index)
+            .and_then(Option::as_deref)
+            .ok_or(IntegrityError::MissingIndex { index })
+    }
+
+    pub fn file_count(&self, index: usize) -> Result<usize, IntegrityError> {
+        Ok(self.index_file(index)?.len() / INDEX_SIZE)
+    }
+
+    pub fn entry(&self, index: usize, file: usize) -> Result<IndexEntry, IntegrityError> {
+        let index_file = self.index_file(index)?;
+        // ids past the end of the index are missing, including those whose offset overflows
+        file.checked_mul(INDEX_SIZE)
+            .and_then(|start| index_file.get(start..start.checked_add(INDEX_SIZE)?))
+            .map(IndexEntry::decode)
+            .ok_or(IntegrityError::MissingFile { index, file })
+    }
+
+    /// reads a file by following its sector chain, checking the header of every sector
+    pub fn read(&self, index: usize, file: usize) -> Result<Vec<u8>, IntegrityError> {
+        let entry = self.entry(index, file)?;
+        let mut buffer = Vec::with_capacity(entry.size);
+        let mut sector = entry.sector;
+        let mut chunk = 0;
+
+        while buffer.len() < entry.size {
+            let chunk_size = (entry.size - buffer.len()).min(CHUNK_SIZE);
+            let Some(block) = (sector > 0)
+                .then(|| {
+                    let start = sector.checked_mul(BLOCK_SIZE)?;
+                    self.data
+                        .get(start..start.checked_add(HEADER_SIZE + chunk_size)?)
+                })
+                .flatten()
+            else {
+                return Err(IntegrityError::SectorOutOfRange {
+                    index,
+                    file,
+                    chunk,
+                    sector,
+                });
+            };
+
+            let (header, data) = block.split_at(HEADER_SIZE);
+            let header_file = (usize::from(header[0]) << 8) | usize::from(header[1]);
+            let header_chunk = (usize::from(header[2]) << 8) | usize::from(header[3]);
+            let next_sector = read_u24(&header[4..7]);
+            let header_index = usize::from(header[7]);
+
+            if header_chunk != chunk {
+                return Err(IntegrityError::ChunkMismatch {
+                    index,
+                    file,
+                    chunk,
+                    found: header_chunk,
+                });
+            }
+            if header_file != file {
+                return Err(IntegrityError::FileIdMismatch {
+                    index,
+                    file,
+                    chunk,
+                    found: header_file,
+                });
+            }
+            // the header stores the index plus one
+            if header_index != index + 1 {
+                return Err(IntegrityError::IndexMismatch {
+                    index,
+                    file,
+                    chunk,
+                    found: header_index,
+                });
+            }
+
+            buffer.extend_from_slice(data);
+            sector = next_sector;
+            chunk += 1;
+        }
+
+        Ok(buffer)
+    }
+
+    /// reads and decodes a file of the archive index
+    pub fn archive(&self, file: usize) -> Result<Archive> {
+        let data = self.read(ARCHIVE_INDEX, file)?;
+        Archive::decode(&data).with_context(|| format!("could not decode archive {file}"))
+    }
+}
@@ -0,0 +1,732 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+
+/// the length of `Player.playerEquipment`
+pub const EQUIPMENT_SLOTS: usize = 14;
+/// the length of `Player.playerItems`
+pub const INVENTORY_SIZE: usize = 28;
+/// `ItemConstants.BANK_SIZE`
+pub const BANK_SIZE: usize = 352;
+/// the length of `Player.playerLevel` and `Player.playerXP`
+pub const SKILL_COUNT: usize = 25;
+/// the length of `Player.friends` and `Player.ignores`
+pub const CONTACT_SLOTS: usize = 200;
+/// the most experience a skill can hold, see `PlayerAssistant.addSkillXP`
+pub const MAX_XP: i32 = 200_000_000;
+
+/// the skills named in `Constants`, indexed by skill id
+pub const SKILL_NAMES: [&str; 21] = [
+    "attack",
+    "defence",
+    "strength",
+    "hitpoints",
+    "ranged",
+    "prayer",
+    "magic",
+    "cooking",
+    "woodcutting",
+    "fletching",
+    "fishing",
+    "firemaking",
+    "crafting",
+    "smithing",
+    "mining",
+    "herblore",
+    "agility",
+    "thieving",
+    "slayer",
+    "farming",
+    "runecrafting",
+];
+
+/// the `[CHARACTER]` keys holding quest stages
+pub const QUEST_KEYS: [&str; 15] = [
+    "cookAss",
+    "sheepShear",
+    "runeMist",
+    "doricQuest",
+    "romeo-juliet",
+    "restGhost",
+    "impsC",
+    "witchspot",
+    "blackKnight",
+    "shieldArrav",
+    "vampSlayer",
+    "pirateTreasure",
+    "knightS",
+    "gertCat",
+    "lostCity",
+];
+
+const ACCOUNT: &str = "ACCOUNT";
+const CHARACTER: &str = "CHARACTER";
+const EQUIPMENT: &str = "EQUIPMENT";
+const LOOK: &str = "LOOK";
+const SKILLS: &str = "SKILLS";
+const ITEMS: &str = "ITEMS";
+const BANK: &str = "BANK";
+const FRIENDS: &str = "FRIENDS";
+const IGNORES: &str = "IGNORES";
+const EOF: &str = "EOF";
+
+/// the sections in the order `PlayerSave.saveGame` writes them
+const SECTIONS: [&str; 9] = [
+    ACCOUNT, CHARACTER, EQUIPMENT, LOOK, SKILLS, ITEMS, BANK, FRIENDS, IGNORES,
+];
+
+/// a `key = value` line, the value is kept as written
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct Property {
+    pub key: String,
+    pub value: String,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct EquipmentSlot {
+    pub slot: usize,
+    /// -1 for an empty slot
+    pub item_id: i32,
+    pub amount: i32,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LookPart {
+    pub part: usize,
+    pub value: i32,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Skill {
+    pub skill: usize,
+    /// the current level, which may be boosted or drained
+    pub level: i32,
+    pub xp: i32,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ItemSlot {
+    pub slot: usize,
+    /// the item id, the save stores it plus one
+    pub item_id: i32,
+    pub amount: i32,
+    /// the price a bot shop charges, only saved in the bank of bots
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price: Option<i32>,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Contact {
+    pub slot: usize,
+    /// the name encoded by `Misc.playerNameToInt64`
+    pub name_hash: i64,
+}
+
+impl Contact {
+    pub fn name(&self) -> String {
+        decode_name(self.name_hash)
+    }
+}
+
+/// a line the server does not read, written back at the end of its section
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct UnknownLine {
+    pub section: String,
+    pub line: String,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Position {
+    pub x: i32,
+    pub y: i32,
+    pub height: i32,
+}
+
+/// a player save from `data/characters/<name>.txt` as read by `PlayerSave.loadGame`
+#[derive(Serialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct CharacterSave {
+    /// every `[ACCOUNT]` line in file order
+    pub account: Vec<Property>,
+    /// every `[CHARACTER]` line in file order, including repeated keys
+    pub character: Vec<Property>,
+    pub equipment: Vec<EquipmentSlot>,
+    pub look: Vec<LookPart>,
+    pub skills: Vec<Skill>,
+    pub inventory: Vec<ItemSlot>,
+    pub bank: Vec<ItemSlot>,
+    pub friends: Vec<Contact>,
+    pub ignores: Vec<Contact>,
+    pub unknown: Vec<UnknownLine>,
+    /// the blank lines at the end of each section where there are not one, kept so that
+    /// rewriting the save keeps its layout
+    #[serde(skip)]
+    pub blank_lines: BTreeMap<String, usize>,
+}
+
+impl CharacterSave {
+    pub fn parse(contents: &str) -> Result<CharacterSave> {
+        let mut save = CharacterSave::default();
+        let mut section: Option<String> = None;
+        let mut blank_lines = 0;
+        for (index, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                blank_lines += 1;
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                if let Some(previous) = section.take() {
+                    if blank_lines != 1 {
+                        save.blank_lines.insert(previous, blank_lines);
+                    }
+                }
+                blank_lines = 0;
+                if name == EOF {
+                    break;
+                }
+                section = Some(name.to_string());
+                continue;
+            }
+            blank_lines = 0;
+            // the server ignores lines before the first section
+            let Some(section) = section.as_deref() else {
+                continue;
+            };
+            save.parse_line(section, line)
+                .with_context(|| format!("could not parse line {}: {line}", index + 1))?;
+        }
+        Ok(save)
+    }
+
+    fn parse_line(&mut self, section: &str, line: &str) -> Result<()> {
+        let Some((key, value)) = line.split_once('=') else {
+            return self.push_unknown(section, line);
+        };
+        let (key, value) = (key.trim(), value.trim());
+        let expected_key = match section {
+            ACCOUNT | CHARACTER => {
+                let property = Property {
+                    key: key.to_string(),
+                    value: value.to_string(),
+                };
+                match section {
+                    ACCOUNT => self.account.push(property),
+                    _ => self.character.push(property),
+                }
+                return Ok(());
+            }
+            EQUIPMENT => "character-equip",
+            LOOK => "character-look",
+            SKILLS => "character-skill",
+            ITEMS => "character-item",
+            BANK => "character-bank",
+            FRIENDS => "character-friend",
+            IGNORES => "character-ignore",
+            _ => return self.push_unknown(section, line),
+        };
+        if key != expected_key {
+            return self.push_unknown(section, line);
+        }
+
+        let fields = value
+            .split('\t')
+            .filter(|field| !field.is_empty())
+            .collect::<Vec<_>>();
+        let int = |index: usize| -> Result<i32> {
+            let field = fields
+                .get(index)
+                .with_context(|| format!("expected at least {} values", index + 1))?;
+            field
+                .parse()
+                .with_context(|| format!("could not parse {field} as an integer"))
+        };
+        let slot = |limit: usize| -> Result<usize> {
+            match usize::try_from(int(0)?) {
+                Ok(slot) if slot < limit => Ok(slot),
+                _ => bail!("slot {} is not below {limit}", fields[0]),
+            }
+        };
+
+        match section {
+            EQUIPMENT => self.equipment.push(EquipmentSlot {
+                slot: slot(EQUIPMENT_SLOTS)?,
+                item_id: int(1)?,
+                amount: int(2)?,
+            }),
+            LOOK => self.look.push(LookPart {
+                part: int(0)?.try_into().context("negative look part")?,
+                value: int(1)?,
+            }),
+            SKILLS => self.skills.push(Skill {
+                skill: slot(SKILL_COUNT)?,
+                level: int(1)?,
+                xp: int(2)?,
+            }),
+            ITEMS => self.inventory.push(ItemSlot {
+                slot: slot(INVENTORY_SIZE)?,
+                item_id: int(1)? - 1,
+                amount: int(2)?,
+                price: None,
+            }),
+            BANK => self.bank.push(ItemSlot {
+                slot: slot(BANK_SIZE)?,
+                item_id: int(1)? - 1,
+                amount: int(2)?,
+                price: fields.get(3).map(|_| int(3)).transpose()?,
+            }),
+            _ => {
+                let name_hash = fields
+                    .get(1)
+                    .context("expected at least 2 values")?
+                    .parse()
+                    .with_context(|| format!("could not parse {} as a name", fields[1]))?;
+                let contact = Contact {
+                    slot: slot(CONTACT_SLOTS)?,
+                    name_hash,
+                };
+                match section {
+                    FRIENDS => self.friends.push(contact),
+                    _ => self.ignores.push(contact),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn push_unknown(&mut self, section: &str, line: &str) -> Result<()> {
+        self.unknown.push(UnknownLine {
+            section: section.to_string(),
+            line: line.to_string(),
+        });
+        Ok(())
+    }
+
+    /// writes the save in the layout of `PlayerSave.saveGame`
+    pub fn to_save_string(&self) -> String {
+        let mut out = String::new();
+        let mut section = |name: &str, lines: Vec<String>| {
+            let _ = writeln!(out, "[{name}]");
+            let unknown = self
+                .unknown
+                .iter()
+                .filter(|unknown| unknown.section == name)
+                .map(|unknown| unknown.line.clone());
+            for line in lines.into_iter().chain(unknown) {
+                let _ = writeln!(out, "{line}");
+            }
+            let blank_lines = self.blank_lines.get(name).copied().unwrap_or(1);
+            out.push_str(&"\n".repeat(blank_lines));
+        };
+
+        let properties = |properties: &[Property]| {
+            properties
+                .iter()
+                .map(|p| format!("{} = {}", p.key, p.value))
+                .collect()
+        };
+        section(ACCOUNT, properties(&self.account));
+        section(CHARACTER, properties(&self.character));
+        section(
+            EQUIPMENT,
+            sorted_by_slot(&self.equipment, |e| e.slot)
+                .map(|e| format!("character-equip = {}\t{}\t{}", e.slot, e.item_id, e.amount))
+                .collect(),
+        );
+        section(
+            LOOK,
+            sorted_by_slot(&self.look, |l| l.part)
+                .map(|l| format!("character-look = {}\t{}", l.part, l.value))
+                .collect(),
+        );
+        section(
+            SKILLS,
+            sorted_by_slot(&self.skills, |s| s.skill)
+                .map(|s| format!("character-skill = {}\t{}\t{}", s.skill, s.level, s.xp))
+                .collect(),
+        );
+        section(ITEMS, item_lines("character-item", &self.inventory));
+        section(BANK, item_lines("character-bank", &self.bank));
+        section(FRIENDS, contact_lines("character-friend", &self.friends));
+        section(IGNORES, contact_lines("character-ignore", &self.ignores));
+
+        let mut other_sections = Vec::new();
+        for unknown in &self.unknown {
+            if !SECTIONS.contains(&unknown.section.as_str())
+                && !other_sections.contains(&unknown.section)
+            {
+                other_sections.push(unknown.section.clone());
+            }
+        }
+        for name in other_sections {
+            section(&name, Vec::new());
+        }
+
+        out.push_str("[EOF]\n");
+        out
+    }
+
+    /// the first value of the `[ACCOUNT]` key
+    pub fn account_property(&self, key: &str) -> Option<&str> {
+        self.account
+            .iter()
+            .find(|p| p.key == key)
+            .map(|p| p.value.as_str())
+    }
+
+    /// the first value of the `[CHARACTER]` key
+    pub fn property(&self, key: &str) -> Option<&str> {
+        self.character
+            .iter()
+            .find(|p| p.key == key)
+            .map(|p| p.value.as_str())
+    }
+
+    /// the first value of the `[CHARACTER]` key parsed as an integer
+    pub fn int_property(&self, key: &str) -> Result<Option<i32>> {
+        self.property(key)
+            .map(|value| {
+                value
+                    .parse()
+                    .with_context(|| format!("could not parse {key} = {value} as an integer"))
+            })
+            .transpose()
+    }
+
+    /// replaces the first value of the `[CHARACTER]` key or appends it
+    pub fn set_property(&mut self, key: &str, value: impl ToString) {
+        let value = value.to_string();
+        match self.character.iter_mut().find(|p| p.key == key) {
+            Some(property) => property.value = value,
+            None => self.character.push(Property {
+                key: key.to_string(),
+                value,
+            }),
+        }
+    }
+
+    /// replaces the first value of the `[ACCOUNT]` key or appends it
+    pub fn set_account_property(&mut self, key: &str, value: impl ToString) {
+        let value = value.to_string();
+        match self.account.iter_mut().find(|p| p.key == key) {
+            Some(property) => property.value = value,
+            None => self.account.push(Property {
+                key: key.to_string(),
+                value,
+            }),
+        }
+    }
+
+    pub fn username(&self) -> Option<&str> {
+        self.account_property("character-username")
+    }
+
+    /// the saved password, either plaintext or `PlayerSave.passwordHash` output
+    pub fn password(&self) -> Option<&str> {
+        self.account_property("character-password")
+    }
+
+    pub fn set_password(&mut self, password: &str) {
+        self.set_account_property("character-password", password);
+    }
+
+    pub fn rights(&self) -> Result<i32> {
+        Ok(self.int_property("character-rights")?.unwrap_or(0))
+    }
+
+    /// `Boolean.parseBoolean` of the saved `isBot`
+    pub fn is_bot(&self) -> bool {
+        self.property("isBot")
+            .is_some_and(|value| value.eq_ignore_ascii_case("true"))
+    }
+
+    pub fn position(&self) -> Result<Position> {
+        Ok(Position {
+            x: self.int_property("character-posx")?.unwrap_or(0),
+            y: self.int_property("character-posy")?.unwrap_or(0),
+            height: self.int_property("character-height")?.unwrap_or(0),
+        })
+    }
+
+    pub fn set_position(&mut self, position: Position) {
+        self.set_property("character-height", position.height);
+        self.set_property("character-posx", position.x);
+        self.set_property("character-posy", position.y);
+    }
+
+    /// removes every value of the `[CHARACTER]` key, returning the removed values
+    pub fn remove_property(&mut self, key: &str) -> Vec<String> {
+        let (removed, kept) = std::mem::take(&mut self.character)
+            .into_iter()
+            .partition(|p| p.key == key);
+        self.character = kept;
+        removed.into_iter().map(|p: Property| p.value).collect()
+    }
+
+    /// removes every value of the `[ACCOUNT]` key, returning the removed values
+    pub fn remove_account_property(&mut self, key: &str) -> Vec<String> {
+        let (removed, kept) = std::mem::take(&mut self.account)
+            .into_iter()
+            .partition(|p| p.key == key);
+        self.account = kept;
+        removed.into_iter().map(|p: Property| p.value).collect()
+    }
+
+    /// whether any inventory, equipment or bank slot holds the item
+    pub fn holds_item(&self, item_id: i32) -> bool {
+        self.equipment.iter().any(|slot| slot.item_id == item_id)
+            || self.inventory.iter().any(|slot| slot.item_id == item_id)
+            || self.bank.iter().any(|slot| slot.item_id == item_id)
+    }
+
+    /// the stage of every quest with a saved stage
+    pub fn quest_stages(&self) -> Result<Vec<(&'static str, i32)>> {
+        let mut stages = Vec::new();
+        for key in QUEST_KEYS {
+            if let Some(stage) = self.int_property(key)? {
+                stages.push((key, stage));
+            }
+        }
+        Ok(stages)
+    }
+
+    /// sets the experience of a skill and restores its level to the level for that experience
+    pub fn set_skill_xp(&mut self, skill: usize, xp: i32) -> Result<()> {
+        if skill >= SKILL_COUNT {
+            bail!("skill {skill} is not below {SKILL_COUNT}");
+        }
+        if !(0..=MAX_XP).contains(&xp) {
+            bail!("experience {xp} is not within 0..={MAX_XP}");
+        }
+        let level = level_for_xp(xp);
+        match self.skills.iter_mut().find(|s| s.skill == skill) {
+            Some(entry) => {
+                entry.level = level;
+                entry.xp = xp;
+            }
+            None => self.skills.push(Skill { skill, level, xp }),
+        }
+        Ok(())
+    }
+
+    /// adds the item to the bank stack holding it or the first free slot, returning the slot
+    pub fn add_bank_item(&mut self, item_id: i32, amount: i32) -> Result<usize> {
+        if item_id < 0 {
+            bail!("item id {item_id} is negative");
+        }
+        if amount <= 0 {
+            bail!("amount {amount} is not positive");
+        }
+        if let Some(entry) = self.bank.iter_mut().find(|s| s.item_id == item_id) {
+            entry.amount = entry.amount.checked_add(amount).with_context(|| {
+                format!("bank slot {} would hold more than {}", entry.slot, i32::MAX)
+            })?;
+            return Ok(entry.slot);
+        }
+        let Some(slot) = (0..BANK_SIZE).find(|slot| self.bank.iter().all(|s| s.slot != *slot))
+        else {
+            bail!("the bank is full");
+        };
+        // bots save a price for every bank slot
+        let price = self.bank.iter().any(|s| s.price.is_some()).then_some(1);
+        self.bank.push(ItemSlot {
+            slot,
+            item_id,
+            amount,
+            price,
+        });
+        Ok(slot)
+    }
+}
+
+fn sorted_by_slot<T>(entries: &[T], slot: impl Fn(&T) -> usize) -> impl Iterator<Item = &T> {
+    let mut sorted = entries.iter().collect::<Vec<_>>();
+    sorted.sort_by_key(|entry| slot(entry));
+    sorted.into_iter()
+}
+
+fn item_lines(key: &str, items: &[ItemSlot]) -> Vec<String> {
+    sorted_by_slot(items, |i| i.slot)
+        .map(|i| {
+            let price = i.price.map(|p| format!("\t{p}")).unwrap_or_default();
+            format!("{key} = {}\t{}\t{}{price}", i.slot, i.item_id + 1, i.amount)
+        })
+        .collect()
+}
+
+fn contact_lines(key: &str, contacts: &[Contact]) -> Vec<String> {
+    sorted_by_slot(contacts, |c| c.slot)
+        .map(|c| format!("{key} = {}\t{}", c.slot, c.name_hash))
+        .collect()
+}
+
+/// mirrors `PlayerAssistant.getLevelForXP`
+pub fn level_for_xp(xp: i32) -> i32 {
+    let mut points = 0;
+    for level in 1..99 {
+        points += (f64::from(level) + 300.0 * 2f64.powf(f64::from(level) / 10.0)).floor() as i32;
+        if xp < points / 12 {
+            return level;
+        }
+    }
+    99
+}
+
+/// the skill id for a skill name or id
+pub fn parse_skill(s: &str) -> Result<usize> {
+    if let Ok(skill) = s.parse::<usize>() {
+        if skill < SKILL_COUNT {
+            return Ok(skill);
+        }
+        bail!("skill {skill} is not below {SKILL_COUNT}");
+    }
+    let name = s.to_lowercase();
+    SKILL_NAMES
+        .iter()
+        .position(|skill| *skill == name)
+        .with_context(|| format!("unknown skill {s}"))
+}
+
+pub fn skill_name(skill: usize) -> String {
+    SKILL_NAMES
+        .get(skill)
+        .map_or_else(|| format!("skill {skill}"), |name| name.to_string())
+}
+
+/// decodes a name encoded by `Misc.playerNameToInt64` the way the client does
+pub fn decode_name(mut name_hash: i64) -> String {
+    const CHARACTERS: &[u8; 37] = b"_abcdefghijklmnopqrstuvwxyz0123456789";
+    let mut name = Vec::new();
+    while name_hash > 0 {
+        name.push(CHARACTERS[(name_hash % 37) as usize] as char);
+        name_hash /= 37;
+    }
+    name.iter().rev().collect::<String>().replace('_', " ")
+}
+
+/// mirrors `Misc.playerNameToInt64`, characters other than letters and digits encode as spaces
+pub fn encode_name(name: &str) -> i64 {
+    let mut name_hash: i64 = 0;
+    for c in name.chars() {
+        name_hash = name_hash.wrapping_mul(37);
+        name_hash = name_hash.wrapping_add(match c {
+            'A'..='Z' => 1 + c as i64 - 'A' as i64,
+            'a'..='z' => 1 + c as i64 - 'a' as i64,
+            '0'..='9' => 27 + c as i64 - '0' as i64,
+            _ => 0,
+        });
+    }
+    while name_hash % 37 == 0 && name_hash != 0 {
+        name_hash /= 37;
+    }
+    name_hash
+}
+
+pub fn save_path(characters_dir: &Path, name: &str) -> PathBuf {
+    characters_dir.join(format!("{name}.txt"))
+}
+
+/// the name and path of every save in the directory
+pub fn save_paths(characters_dir: &Path) -> Result<Vec<(String, PathBuf)>> {
+    let mut paths = Vec::new();
+    let entries = std::fs::read_dir(characters_dir)
+        .with_context(|| format!("could not read {}", characters_dir.display()))?;
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "txt") {
+            if let Some(name) = path.file_stem().and_then(|stem| stem.to_str()) {
+                paths.push((name.to_string(), path.clone()));
+            }
+        }
+    }
+    paths.sort();
+    Ok(paths)
+}
+
+pub fn load(path: &Path) -> Result<CharacterSave> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("could not read character from {}", path.display()))?;
+    CharacterSave::parse(&contents)
+        .with_context(|| format!("could not parse character from {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// a save in the layout of `PlayerSave.saveGame`, with the [CHARACTER] section followed by
+    /// two blank lines and a bot's bank prices
+    const SAVE: &str = "[ACCOUNT]
+character-username = Zezima
+character-password = V0xgRb81hNQ0TnzWJdV+pSUN2Q+aXPyrJjNTPf4QnNE=
+
+[CHARACTER]
+character-height = 0
+character-posx = 3222
+character-posy = 3218
+character-rights = 0
+isBot = true
+barrowsNpcs = 0\t0
+barrowsNpcs = 1\t0
+discord-user-id = 0
+
+
+[EQUIPMENT]
+character-equip = 0\t-1\t0
+character-equip = 3\t4151\t1
+
+[LOOK]
+character-look = 0\t1
+character-look = 1\t10
+
+[SKILLS]
+character-skill = 0\t1\t0
+character-skill = 3\t10\t1300
+
+[ITEMS]
+character-item = 0\t996\t25000
+
+[BANK]
+character-bank = 0\t996\t1000\t5
+character-bank = 1\t4152\t1
+
+[FRIENDS]
+character-friend = 0\t1183847
+
+[IGNORES]
+
+[EOF]
+";
+
+    #[test]
+    fn writes_the_save_back_unchanged() -> Result<()> {
+        let save = CharacterSave::parse(SAVE)?;
+        assert_eq!(save.username(), Some("Zezima"));
+        assert!(save.is_bot());
+        assert_eq!(save.inventory[0].item_id, 995);
+        assert_eq!(save.bank[0].price, Some(5));
+        assert_eq!(save.to_save_string(), SAVE);
+        Ok(())
+    }
+
+    #[test]
+    fn changes_only_the_edited_lines() -> Result<()> {
+        let mut save = CharacterSave::parse(SAVE)?;
+        save.set_position(Position {
+            x: 3093,
+            y: 3493,
+            height: 1,
+        });
+        let expected = SAVE
+            .replace("character-height = 0", "character-height = 1")
+            .replace("character-posx = 3222", "character-posx = 3093")
+            .replace("character-posy = 3218", "character-posy = 3493");
+        assert_eq!(save.to_save_string(), expected);
+        assert_eq!(CharacterSave::parse(&expected)?, save);
+        Ok(())
+    }
+}
@@ -0,0 +1,472 @@
+use std::collections::{HashMap, VecDeque};
+
+use serde::Serialize;
+
+use super::{
+    map::{Region, BLOCKED_TILE, MAP_PLANES, MAP_WIDTH},
+    object_decoder::ObjectDefinition,
+    spawns::region_id,
+};
+
+/// a floor tile which can not be walked on
+pub const BLOCKED_FLOOR: u32 = 0x200000;
+/// a tile covered by a solid object
+pub const SOLID_OBJECT: u32 = 0x100;
+/// added to [`SOLID_OBJECT`] for clipped objects
+pub const CLIPPED_OBJECT: u32 = 0x20000;
+
+/// the flags which stop movement onto a tile from each direction, as used by `PathFinder`
+const BLOCKED_SOUTH: u32 = 0x1280102;
+const BLOCKED_WEST: u32 = 0x1280108;
+const BLOCKED_NORTH: u32 = 0x1280120;
+const BLOCKED_EAST: u32 = 0x1280180;
+const BLOCKED_SOUTH_WEST: u32 = 0x128010e;
+const BLOCKED_NORTH_WEST: u32 = 0x1280138;
+const BLOCKED_SOUTH_EAST: u32 = 0x1280183;
+const BLOCKED_NORTH_EAST: u32 = 0x12801e0;
+
+const STRAIGHT_WALL: u8 = 0;
+const DIAGONAL_CORNER_WALL: u8 = 1;
+const CORNER_WALL: u8 = 2;
+const RECTANGULAR_CORNER_WALL: u8 = 3;
+const DIAGONAL_WALL: u8 = 9;
+const GROUND_DECORATION: u8 = 22;
+
+/// the walking clipping of the whole map, mirrors `com.rs2.world.clip.Region`
+/// tiles outside of the decoded regions have no flags, like in the server
+pub struct CollisionMap {
+    regions: HashMap<i32, Vec<u32>>,
+    /// the object which made a tile [`SOLID_OBJECT`]
+    occupants: HashMap<(i32, i32, i32), i32>,
+}
+
+impl CollisionMap {
+    /// adds the blocked floors and objects of every region, like `RegionFactory.loadMaps`
+    pub fn build<'a>(
+        regions: impl IntoIterator<Item = &'a Region>,
+        objects: &[ObjectDefinition],
+    ) -> Self {
+        let regions = regions.into_iter().collect::<Vec<_>>();
+        let mut map = Self {
+            regions: regions
+                .iter()
+                .map(|region| {
+                    let tiles = (MAP_PLANES * MAP_WIDTH * MAP_WIDTH) as usize;
+                    (region.index.region_id, vec![0; tiles])
+                })
+                .collect(),
+            occupants: HashMap::new(),
+        };
+
+        for region in &regions {
+            let (base_x, base_y) = region.base();
+            for plane in 0..MAP_PLANES {
+                for x in 0..MAP_WIDTH {
+                    for y in 0..MAP_WIDTH {
+                        if region.tile(plane, x, y).attributes & BLOCKED_TILE == 0 {
+                            continue;
+                        }
+                        // tiles below bridges end up on plane -1 and are dropped
+                        let plane = if region.is_bridge(x, y) {
+                            plane - 1
+                        } else {
+                            plane
+                        };
+                        if plane >= 0 {
+                            map.add(base_x + x, base_y + y, plane, BLOCKED_FLOOR);
+                        }
+                    }
+                }
+            }
+        }
+
+        for region in &regions {
+            let (base_x, base_y) = region.base();
+            for object in &region.objects {
+                // unlike the client, the server also drops objects below bridges on plane 0
+                let plane = if region.is_bridge(object.local_x, object.local_y) {
+                    object.plane - 1
+                } else {
+                    object.plane
+                };
+                if plane < 0 {
+                    continue;
+                }
+                if let Some(definition) = objects.get(object.id as usize) {
+                    map.add_object(
+                        definition,
+                        base_x + object.local_x,
+                        base_y + object.local_y,
+                        plane,
+                        object.object_type,
+                        object.orientation,
+                    );
+                }
+            }
+        }
+
+        map
+    }
+
+    fn tile_offset(x: i32, y: i32, plane: i32) -> usize {
+        ((plane * MAP_WIDTH + (x & (MAP_WIDTH - 1))) * MAP_WIDTH + (y & (MAP_WIDTH - 1))) as usize
+    }
+
+    /// `Region.addClipping`, which ignores tiles outside of the decoded regions
+    fn add(&mut self, x: i32, y: i32, plane: i32, flags: u32) {
+        if let Some(tiles) = self.regions.get_mut(&region_id(x, y)) {
+            tiles[Self::tile_offset(x, y, plane)] |= flags;
+        }
+    }
+
+    /// whether the tile belongs to a region with a map
+    pub fn has_map(&self, x: i32, y: i32) -> bool {
+        self.regions.contains_key(&region_id(x, y))
+    }
+
+    /// `Region.getClipping`, which reads planes above 3 as plane 0, planes below 0 have no flags
+    pub fn flags(&self, x: i32, y: i32, plane: i32) -> u32 {
+        let plane = if plane >= MAP_PLANES { 0 } else { plane };
+        if plane < 0 {
+            return 0;
+        }
+        self.regions
+            .get(&region_id(x, y))
+            .map_or(0, |tiles| tiles[Self::tile_offset(x, y, plane)])
+    }
+
+    /// whether nothing can stand on the tile because of its floor or a solid object
+    pub fn is_blocked(&self, x: i32, y: i32, plane: i32) -> bool {
+        self.flags(x, y, plane) & (BLOCKED_FLOOR | SOLID_OBJECT) != 0
+    }
+
+    /// the object covering the tile if it is blocked by a solid object
+    pub fn occupant(&self, x: i32, y: i32, plane: i32) -> Option<i32> {
+        self.occupants.get(&(x, y, plane)).copied()
+    }
+
+    /// why nothing can stand on the tile, if anything
+    pub fn blockage(&self, x: i32, y: i32, plane: i32) -> Option<Blockage> {
+        let flags = self.flags(x, y, plane);
+        if !self.has_map(x, y) {
+            Some(Blockage::NoMap)
+        } else if flags & BLOCKED_FLOOR != 0 {
+            Some(Blockage::BlockedFloor)
+        } else if flags & SOLID_OBJECT != 0 {
+            Some(Blockage::SolidObject {
+                object_id: self.occupant(x, y, plane),
+            })
+        } else {
+            None
+        }
+    }
+
+    /// `Region.addObject` without the projectile clipping
+    fn add_object(
+        &mut self,
+        definition: &ObjectDefinition,
+        x: i32,
+        y: i32,
+        plane: i32,
+        object_type: u8,
+        orientation: u8,
+    ) {
+        if !definition.solid {
+            return;
+        }
+        let (width, length) = if orientation == 1 || orientation == 3 {
+            (definition.length, definition.width)
+        } else {
+            (definition.width, definition.length)
+        };
+
+        match object_type {
+            GROUND_DECORATION if definition.interactive => self.add(x, y, plane, BLOCKED_FLOOR),
+            GROUND_DECORATION => {}
+            DIAGONAL_WALL.. => {
+                let flags = if definition.clipped {
+                    SOLID_OBJECT | CLIPPED_OBJECT
+                } else {
+                    SOLID_OBJECT
+                };
+                for tile_x in x..x + width {
+                    for tile_y in y..y + length {
+                        self.add(tile_x, tile_y, plane, flags);
+                        self.occupants
+                            .insert((tile_x, tile_y, plane), definition.id);
+                    }
+                }
+            }
+            STRAIGHT_WALL..=RECTANGULAR_CORNER_WALL => {
+                self.add_wall(x, y, plane, object_type, orientation, definition.clipped)
+            }
+            _ => {}
+        }
+    }
+
+    /// `Region.addClippingForVariableObject`
+    fn add_wall(
+        &mut self,
+        x: i32,
+        y: i32,
+        plane: i32,
+        wall_type: u8,
+        orientation: u8,
+        clipped: bool,
+    ) {
+        let walls: &[(i32, i32, u32)] = match (wall_type, orientation) {
+            (STRAIGHT_WALL, 0) => &[(0, 0, 128), (-1, 0, 8)],
+            (STRAIGHT_WALL, 1) => &[(0, 0, 2), (0, 1, 32)],
+            (STRAIGHT_WALL, 2) => &[(0, 0, 8), (1, 0, 128)],
+            (STRAIGHT_WALL, _) => &[(0, 0, 32), (0, -1, 2)],
+            (DIAGONAL_CORNER_WALL | RECTANGULAR_CORNER_WALL, 0) => &[(0, 0, 1), (-1, 0, 16)],
+            (DIAGONAL_CORNER_WALL | RECTANGULAR_CORNER_WALL, 1) => &[(0, 0, 4), (1, 1, 64)],
+            (DIAGONAL_CORNER_WALL | RECTANGULAR_CORNER_WALL, 2) => &[(0, 0, 16), (1, -1, 1)],
+            (DIAGONAL_CORNER_WALL | RECTANGULAR_CORNER_WALL, _) => &[(0, 0, 64), (-1, -1, 4)],
+            (CORNER_WALL, 0) => &[(0, 0, 130), (-1, 0, 8), (0, 1, 32)],
+            (CORNER_WALL, 1) => &[(0, 0, 10), (0, 1, 32), (1, 0, 128)],
+            (CORNER_WALL, 2) => &[(0, 0, 40), (1, 0, 128), (0, -1, 2)],
+            (CORNER_WALL, _) => &[(0, 0, 160), (0, -1, 2), (-1, 0, 8)],
+            _ => &[],
+        };
+        for (dx, dy, flags) in walls {
+            self.add(x + dx, y + dy, plane, *flags);
+        }
+
+        // the extra flags of clipped walls, which no walking mask checks
+        if clipped {
+            let clipped_walls: &[(i32, i32, u32)] = match (wall_type, orientation) {
+                (STRAIGHT_WALL, 0) => &[(0, 0, 65536), (-1, 0, 4096)],
+                (STRAIGHT_WALL, 1) => &[(0, 0, 1024), (0, 1, 16384)],
+                (STRAIGHT_WALL, 2) => &[(0, 0, 4096), (1, 0, 65536)],
+                (STRAIGHT_WALL, _) => &[(0, 0, 16384), (0, -1, 1024)],
+                (DIAGONAL_CORNER_WALL | RECTANGULAR_CORNER_WALL, 0) => {
+                    &[(0, 0, 512), (-1, 1, 8192)]
+                }
+                (DIAGONAL_CORNER_WALL | RECTANGULAR_CORNER_WALL, 1) => {
+                    &[(0, 0, 2048), (1, 1, 32768)]
+                }
+                (DIAGONAL_CORNER_WALL | RECTANGULAR_CORNER_WALL, 2) => &[(0, 0, 8192), (1, 1, 512)],
+                (DIAGONAL_CORNER_WALL | RECTANGULAR_CORNER_WALL, _) => {
+                    &[(0, 0, 32768), (-1, -1, 2048)]
+                }
+                (CORNER_WALL, 0) => &[(0, 0, 66560), (-1, 0, 4096), (0, 1, 16384)],
+                (CORNER_WALL, 1) => &[(0, 0, 5120), (0, 1, 16384), (1, 0, 65536)],
+                (CORNER_WALL, 2) => &[(0, 0, 20480), (1, 0, 65536), (0, -1, 1024)],
+                (CORNER_WALL, _) => &[(0, 0, 81920), (0, -1, 1024), (-1, 0, 4096)],
+                _ => &[],
+            };
+            for (dx, dy, flags) in clipped_walls {
+                self.add(x + dx, y + dy, plane, *flags);
+            }
+        }
+    }
+
+    /// whether a single step in the direction is allowed, checking the same flags as `PathFinder`
+    pub fn can_step(&self, x: i32, y: i32, plane: i32, dx: i32, dy: i32) -> bool {
+        let (to_x, to_y) = (x + dx, y + dy);
+        let free = |x: i32, y: i32, mask: u32| self.flags(x, y, plane) & mask == 0;
+        match (dx, dy) {
+            (0, -1) => free(to_x, to_y, BLOCKED_SOUTH),
+            (-1, 0) => free(to_x, to_y, BLOCKED_WEST),
+            (0, 1) => free(to_x, to_y, BLOCKED_NORTH),
+            (1, 0) => free(to_x, to_y, BLOCKED_EAST),
+            (-1, -1) => {
+                free(to_x, to_y, BLOCKED_SOUTH_WEST)
+                    && free(x - 1, y, BLOCKED_WEST)
+                    && free(x, y - 1, BLOCKED_SOUTH)
+            }
+            (-1, 1) => {
+                free(to_x, to_y, BLOCKED_NORTH_WEST)
+                    && free(x - 1, y, BLOCKED_WEST)
+                    && free(x, y + 1, BLOCKED_NORTH)
+            }
+            (1, -1) => {
+                free(to_x, to_y, BLOCKED_SOUTH_EAST)
+                    && free(x + 1, y, BLOCKED_EAST)
+                    && free(x, y - 1, BLOCKED_SOUTH)
+            }
+            (1, 1) => {
+                free(to_x, to_y, BLOCKED_NORTH_EAST)
+                    && free(x + 1, y, BLOCKED_EAST)
+                    && free(x, y + 1, BLOCKED_NORTH)
+            }
+            _ => false,
+        }
+    }
+
+    /// a breadth first search in the order of `PathFinder.findRoute`, limited to `max_steps`
+    /// returns every tile of the path after `from`, or `None` if `to` can not be reached
+    pub fn find_path(
+        &self,
+        from: (i32, i32),
+        to: (i32, i32),
+        plane: i32,
+        max_steps: usize,
+    ) -> Option<Vec<(i32, i32)>> {
+        const DIRECTIONS: [(i32, i32); 8] = [
+            (0, -1),
+            (-1, 0),
+            (0, 1),
+            (1, 0),
+            (-1, -1),
+            (-1, 1),
+            (1, -1),
+            (1, 1),
+        ];
+
+        // the tile each visited tile was reached from and its distance
+        let mut via: HashMap<(i32, i32), ((i32, i32), usize)> = HashMap::new();
+        let mut queue = VecDeque::from([from]);
+        via.insert(from, (from, 0));
+        while let Some(current) = queue.pop_front() {
+            if current == to {
+                let mut path = Vec::new();
+                let mut tile = current;
+                while tile != from {
+                    path.push(tile);
+                    tile = via[&tile].0;
+                }
+                path.reverse();
+                return Some(path);
+            }
+
+            let steps = via[&current].1 + 1;
+            if steps > max_steps {
+                continue;
+            }
+            for (dx, dy) in DIRECTIONS {
+                let next = (current.0 + dx, current.1 + dy);
+                if !via.contains_key(&next) && self.can_step(current.0, current.1, plane, dx, dy) {
+                    via.insert(next, (current, steps));
+                    queue.push_back(next);
+                }
+            }
+        }
+        None
+    }
+}
+
+/// the tiles of a path where the direction changes, which is what the server queues for walking
+pub fn waypoints(from: (i32, i32), path: &[(i32, i32)]) -> Vec<(i32, i32)> {
+    let mut waypoints: Vec<(i32, i32)> = Vec::new();
+    let mut previous = from;
+    let mut direction = None;
+    for (index, tile) in path.iter().enumerate() {
+        let step = (tile.0 - previous.0, tile.1 - previous.1);
+        if direction.is_some_and(|direction| direction != step) {
+            waypoints.push(path[index - 1]);
+        }
+        direction = Some(step);
+        previous = *tile;
+    }
+    waypoints.extend(path.last());
+    waypoints
+}
+
+/// something placed on a tile which can not hold it
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Blockage {
+    /// the tile is in a region without a map, so the server has no clipping for it
+    NoMap,
+    /// the floor of the tile can not be walked on
+    BlockedFloor,
+    /// the tile is covered by a solid object
+    SolidObject { object_id: Option<i32> },
+    /// the height is not one of the map's planes
+    InvalidHeight,
+}
+
+impl std::fmt::Display for Blockage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Blockage::NoMap => write!(f, "is in a region without a map"),
+            Blockage::BlockedFloor => write!(f, "is on a blocked floor"),
+            Blockage::SolidObject {
+                object_id: Some(object_id),
+            } => write!(f, "is inside of object {object_id}"),
+            Blockage::SolidObject { object_id: None } => write!(f, "is inside of a solid object"),
+            Blockage::InvalidHeight => {
+                write!(f, "is not on a height between 0 and {}", MAP_PLANES - 1)
+            }
+        }
+    }
+}
+
+/// the kinds of content checked by [`check_placements`]
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PlacementKind {
+    NpcSpawn,
+    GlobalDrop,
+    Door,
+}
+
+impl std::fmt::Display for PlacementKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlacementKind::NpcSpawn => write!(f, "npc spawn"),
+            PlacementKind::GlobalDrop => write!(f, "global drop"),
+            PlacementKind::Door => write!(f, "door"),
+        }
+    }
+}
+
+/// an npc, item or door placed on a tile
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Placement {
+    pub kind: PlacementKind,
+    /// the npc, item or object id
+    pub id: i32,
+    pub x: i32,
+    pub y: i32,
+    pub height: i32,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct PlacementProblem {
+    #[serde(flatten)]
+    pub placement: Placement,
+    pub blockage: Blockage,
+}
+
+impl std::fmt::Display for PlacementProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let placement = &self.placement;
+        write!(
+            f,
+            "{} {} at {},{},{} {}",
+            placement.kind, placement.id, placement.x, placement.y, placement.height, self.blockage
+        )
+    }
+}
+
+/// reports every placement on a tile which nothing can stand on
+/// global drops may lie on solid objects such as tables and doors may block their own tile
+pub fn check_placements(
+    map: &CollisionMap,
+    placements: impl IntoIterator<Item = Placement>,
+) -> Vec<PlacementProblem> {
+    placements
+        .into_iter()
+        .filter_map(|placement| {
+            let blockage = if (0..MAP_PLANES).contains(&placement.height) {
+                map.blockage(placement.x, placement.y, placement.height)?
+            } else {
+                Blockage::InvalidHeight
+            };
+            let allowed = match (&blockage, placement.kind) {
+                (Blockage::SolidObject { .. }, PlacementKind::GlobalDrop) => true,
+                (Blockage::SolidObject { object_id }, PlacementKind::Door) => {
+                    *object_id == Some(placement.id)
+                }
+                _ => false,
+            };
+            (!allowed).then_some(PlacementProblem {
+                placement,
+                blockage,
+            })
+        })
+        .collect()
+}
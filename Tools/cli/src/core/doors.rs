@@ -0,0 +1,29 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// mirrors `com.rs2.util.DoorData` as loaded from `data/doors.json` and `data/doubledoors.json`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct DoorData {
+    pub face: i32,
+    pub locations: Vec<DoorLocation>,
+    pub id: i32,
+    /// missing from the double doors
+    #[serde(rename = "type", default, skip_serializing_if = "Option::is_none")]
+    pub door_type: Option<i32>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DoorLocation {
+    pub x: i32,
+    pub y: i32,
+    pub height: i32,
+}
+
+pub fn load_all(path: &Path) -> Result<Vec<DoorData>> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("could not read doors from {}", path.display()))?;
+    serde_json::from_str(&contents)
+        .with_context(|| format!("could not parse doors from {}", path.display()))
+}
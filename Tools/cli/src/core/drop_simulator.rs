@@ -0,0 +1,136 @@
+use std::collections::BTreeMap;
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use rayon::prelude::*;
+use serde::Serialize;
+
+use super::npc_drops::ItemDrop;
+
+/// the outcome of simulating many kills against a single drop table entry
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct EntryStats {
+    pub item_id: i32,
+    pub chance: i32,
+    /// the number of kills that dropped this entry
+    pub drops: u64,
+    /// the sum of all amounts dropped
+    pub total_amount: u64,
+    /// histogram of the number of kills between drops, including the first drop
+    #[serde(skip)]
+    gaps: BTreeMap<u64, u64>,
+}
+
+impl EntryStats {
+    fn new(drop: &ItemDrop) -> Self {
+        Self {
+            item_id: drop.item_id,
+            chance: drop.chance,
+            drops: 0,
+            total_amount: 0,
+            gaps: BTreeMap::new(),
+        }
+    }
+
+    fn merge(&mut self, other: EntryStats) {
+        self.drops += other.drops;
+        self.total_amount += other.total_amount;
+        for (gap, count) in other.gaps {
+            *self.gaps.entry(gap).or_default() += count;
+        }
+    }
+
+    pub fn observed_rate(&self, kills: u64) -> f64 {
+        if kills == 0 {
+            return 0.0;
+        }
+        self.drops as f64 / kills as f64
+    }
+
+    /// the number of kills within which the first drop happens in `percentile` of all cases
+    /// returns `None` if the entry was never dropped
+    pub fn kills_to_first_drop(&self, percentile: f64) -> Option<u64> {
+        let samples: u64 = self.gaps.values().sum();
+        if samples == 0 {
+            return None;
+        }
+        let target = ((percentile / 100.0) * samples as f64).ceil().max(1.0) as u64;
+        let mut seen = 0;
+        for (gap, count) in &self.gaps {
+            seen += count;
+            if seen >= target {
+                return Some(*gap);
+            }
+        }
+        self.gaps.keys().last().copied()
+    }
+}
+
+/// `Misc.random(range)`, returns a number from 0 to range inclusive
+fn misc_random<R: Rng>(rng: &mut R, range: i32) -> i32 {
+    if range <= 0 {
+        0
+    } else {
+        rng.gen_range(0..=range)
+    }
+}
+
+/// `Misc.random(min, max)`, returns a number between and including min and max
+fn misc_random_between<R: Rng>(rng: &mut R, min: i32, max: i32) -> i32 {
+    rng.gen_range(min..=max.max(min))
+}
+
+/// rolls a single kill exactly like `NpcHandler.dropItems`
+/// every entry is rolled in order, but once an entry with a chance above 1 has dropped,
+/// no further entries can drop (`isDropped`); guaranteed drops never use up the roll
+pub fn roll_kill<R: Rng>(table: &[ItemDrop], rng: &mut R, mut on_drop: impl FnMut(usize, i32)) {
+    let mut is_dropped = false;
+    for (index, possible_drop) in table.iter().enumerate() {
+        if misc_random(rng, possible_drop.chance) == 0 && !is_dropped {
+            let amount =
+                misc_random_between(rng, possible_drop.min_amount(), possible_drop.max_amount());
+            on_drop(index, amount);
+            if possible_drop.chance > 1 {
+                is_dropped = true;
+            }
+        }
+    }
+}
+
+fn simulate_chunk(table: &[ItemDrop], kills: u64, mut rng: StdRng) -> Vec<EntryStats> {
+    let mut stats = table.iter().map(EntryStats::new).collect::<Vec<_>>();
+    let mut last_drop = vec![0u64; table.len()];
+    for kill in 1..=kills {
+        roll_kill(table, &mut rng, |index, amount| {
+            let entry = &mut stats[index];
+            entry.drops += 1;
+            entry.total_amount += amount.max(0) as u64;
+            *entry.gaps.entry(kill - last_drop[index]).or_default() += 1;
+            last_drop[index] = kill;
+        });
+    }
+    stats
+}
+
+/// simulates `kills` kills against the drop table in parallel, returning stats per table entry
+/// passing a seed makes the simulation reproducible
+pub fn simulate(table: &[ItemDrop], kills: u64, seed: Option<u64>) -> Vec<EntryStats> {
+    let chunks = (rayon::current_num_threads() as u64 * 4).clamp(1, kills.max(1));
+    let base_seed = seed.unwrap_or_else(rand::random);
+
+    (0..chunks)
+        .into_par_iter()
+        .map(|chunk| {
+            let chunk_kills = kills / chunks + u64::from(chunk < kills % chunks);
+            let rng = StdRng::seed_from_u64(base_seed.wrapping_add(chunk));
+            simulate_chunk(table, chunk_kills, rng)
+        })
+        .reduce(
+            || table.iter().map(EntryStats::new).collect(),
+            |mut total, chunk| {
+                for (entry, other) in total.iter_mut().zip(chunk) {
+                    entry.merge(other);
+                }
+                total
+            },
+        )
+}
@@ -0,0 +1,188 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use serde::Serialize;
+
+use super::{characters::CharacterSave, item_definition::ItemDefinition};
+
+/// the items held by one character, keyed by unnoted item id
+#[derive(Clone, Debug, Default)]
+pub struct CharacterHoldings {
+    pub wealth: CharacterWealth,
+    pub items: HashMap<i32, ItemAmounts>,
+}
+
+#[derive(Serialize, Clone, Debug, Default, PartialEq, Eq)]
+pub struct CharacterWealth {
+    pub name: String,
+    pub inventory: i64,
+    pub equipment: i64,
+    pub bank: i64,
+    pub total: i64,
+}
+
+#[derive(Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ItemAmounts {
+    pub unnoted: i64,
+    pub noted: i64,
+}
+
+impl ItemAmounts {
+    pub fn total(&self) -> i64 {
+        self.unnoted + self.noted
+    }
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct ItemCirculation {
+    /// the unnoted item id, notes are counted towards the item they represent
+    pub item_id: i32,
+    pub name: Option<String>,
+    #[serde(flatten)]
+    pub amounts: ItemAmounts,
+    pub value: i64,
+    /// the number of characters holding the item
+    pub holders: usize,
+    /// the character holding the most of the item
+    pub largest_holder: String,
+    pub largest_amount: i64,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct EconomyReport {
+    pub characters: usize,
+    pub total_wealth: i64,
+    /// the value of every unnoted item held
+    pub unnoted_wealth: i64,
+    /// the value of every noted item held
+    pub noted_wealth: i64,
+    /// the wealthiest characters, most wealthy first
+    pub top_holders: Vec<CharacterWealth>,
+    /// every item held by a character, most valuable in total first
+    pub items: Vec<ItemCirculation>,
+}
+
+/// values items at their definition value, notes at the value of the item they represent
+pub struct Valuation<'a> {
+    items: &'a HashMap<i32, ItemDefinition>,
+}
+
+impl<'a> Valuation<'a> {
+    pub fn new(items: &'a HashMap<i32, ItemDefinition>) -> Self {
+        Valuation { items }
+    }
+
+    /// the unnoted item id and whether the item is a note
+    pub fn unnote(&self, item_id: i32) -> (i32, bool) {
+        match self.items.get(&item_id) {
+            Some(item) if item.is_note() => (item.note_info_id.unwrap_or(item_id), true),
+            _ => (item_id, false),
+        }
+    }
+
+    /// the value of a single unnoted item, 0 for unknown items
+    pub fn value(&self, unnoted_id: i32) -> i64 {
+        self.items
+            .get(&unnoted_id)
+            .map_or(0, |item| i64::from(item.value))
+    }
+
+    pub fn name(&self, unnoted_id: i32) -> Option<String> {
+        self.items
+            .get(&unnoted_id)
+            .and_then(|item| item.name.clone())
+    }
+}
+
+/// counts every inventory, equipment and bank slot of the character
+pub fn character_holdings(
+    name: &str,
+    save: &CharacterSave,
+    valuation: &Valuation,
+) -> CharacterHoldings {
+    let mut items: HashMap<i32, ItemAmounts> = HashMap::new();
+    // adds the slot to the item amounts and returns its value
+    let mut count = |item_id: i32, amount: i32| {
+        if item_id < 0 || amount <= 0 {
+            return 0;
+        }
+        let amount = i64::from(amount);
+        let (unnoted_id, noted) = valuation.unnote(item_id);
+        let amounts = items.entry(unnoted_id).or_default();
+        if noted {
+            amounts.noted += amount;
+        } else {
+            amounts.unnoted += amount;
+        }
+        valuation.value(unnoted_id) * amount
+    };
+
+    let mut wealth = CharacterWealth {
+        name: name.to_string(),
+        ..Default::default()
+    };
+    for slot in &save.equipment {
+        wealth.equipment += count(slot.item_id, slot.amount);
+    }
+    for slot in &save.inventory {
+        wealth.inventory += count(slot.item_id, slot.amount);
+    }
+    for slot in &save.bank {
+        wealth.bank += count(slot.item_id, slot.amount);
+    }
+    wealth.total = wealth.inventory + wealth.equipment + wealth.bank;
+
+    CharacterHoldings { wealth, items }
+}
+
+/// combines the holdings of every character, keeping the `top` wealthiest characters
+pub fn report(holdings: &[CharacterHoldings], valuation: &Valuation, top: usize) -> EconomyReport {
+    let mut items: HashMap<i32, ItemCirculation> = HashMap::new();
+    for character in holdings {
+        for (&item_id, amounts) in &character.items {
+            let circulation = items.entry(item_id).or_insert_with(|| ItemCirculation {
+                item_id,
+                name: valuation.name(item_id),
+                amounts: ItemAmounts::default(),
+                value: 0,
+                holders: 0,
+                largest_holder: String::new(),
+                largest_amount: 0,
+            });
+            circulation.amounts.unnoted += amounts.unnoted;
+            circulation.amounts.noted += amounts.noted;
+            circulation.holders += 1;
+            if amounts.total() > circulation.largest_amount {
+                circulation
+                    .largest_holder
+                    .clone_from(&character.wealth.name);
+                circulation.largest_amount = amounts.total();
+            }
+        }
+    }
+
+    let (mut unnoted_wealth, mut noted_wealth) = (0, 0);
+    for circulation in items.values_mut() {
+        let value = valuation.value(circulation.item_id);
+        circulation.value = value * circulation.amounts.total();
+        unnoted_wealth += value * circulation.amounts.unnoted;
+        noted_wealth += value * circulation.amounts.noted;
+    }
+
+    EconomyReport {
+        characters: holdings.len(),
+        total_wealth: holdings.iter().map(|c| c.wealth.total).sum(),
+        unnoted_wealth,
+        noted_wealth,
+        top_holders: holdings
+            .iter()
+            .map(|c| c.wealth.clone())
+            .sorted_by(|a, b| b.total.cmp(&a.total).then_with(|| a.name.cmp(&b.name)))
+            .take(top)
+            .collect(),
+        items: items
+            .into_values()
+            .sorted_by(|a, b| b.value.cmp(&a.value).then(a.item_id.cmp(&b.item_id)))
+            .collect(),
+    }
+}
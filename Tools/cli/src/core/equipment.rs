@@ -0,0 +1,234 @@
+use std::{io::Read, path::Path};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// the equipment slots from `ItemConstants`
+pub const SLOTS: [(i32, &str); 11] = [
+    (0, "hat"),
+    (1, "cape"),
+    (2, "amulet"),
+    (3, "weapon"),
+    (4, "chest"),
+    (5, "shield"),
+    (7, "legs"),
+    (9, "hands"),
+    (10, "feet"),
+    (12, "ring"),
+    (13, "arrows"),
+];
+
+/// the slot apollo writes for items which can not be equipped
+pub const NOT_EQUIPABLE: i32 = -1;
+
+pub fn slot_name(slot: i32) -> Option<&'static str> {
+    SLOTS
+        .iter()
+        .find(|(id, _)| *id == slot)
+        .map(|(_, name)| *name)
+}
+
+/// parses a slot from its name or number
+pub fn parse_slot(s: &str) -> Result<i32> {
+    if let Some((id, _)) = SLOTS.iter().find(|(_, name)| name.eq_ignore_ascii_case(s)) {
+        return Ok(*id);
+    }
+    s.parse().with_context(|| {
+        let names = SLOTS.iter().map(|(_, name)| *name).collect::<Vec<_>>();
+        format!(
+            "expected a slot number or one of {} but got {s}",
+            names.join(", ")
+        )
+    })
+}
+
+/// the layouts an equipment file can have
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Layout {
+    /// one slot byte per item id without a header, as read by `ItemData.targetSlots`
+    /// this is the layout of `data/data/equipment.dat`
+    Slots,
+    /// a short count, then a slot byte per item id followed by flags and requirements for
+    /// equipable items, as written by `org.apollo.cache.tools.EquipmentUpdater`
+    Apollo,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EquipmentEntry {
+    pub id: i32,
+    /// the `ItemConstants` slot the item is equipped in
+    pub slot: i32,
+    /// only stored by the apollo layout, for items which can be equipped
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<EquipmentDetails>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EquipmentDetails {
+    pub two_handed: bool,
+    pub full_body: bool,
+    pub full_hat: bool,
+    pub full_mask: bool,
+    pub requirements: Requirements,
+}
+
+/// the levels required to equip an item
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(default)]
+pub struct Requirements {
+    pub attack: u8,
+    pub strength: u8,
+    pub defence: u8,
+    pub ranged: u8,
+    pub prayer: u8,
+    pub magic: u8,
+}
+
+impl Requirements {
+    /// the skills with a requirement above 1 in the order apollo writes them
+    pub fn levels(&self) -> Vec<(&'static str, u8)> {
+        [
+            ("attack", self.attack),
+            ("strength", self.strength),
+            ("defence", self.defence),
+            ("ranged", self.ranged),
+            ("prayer", self.prayer),
+            ("magic", self.magic),
+        ]
+        .into_iter()
+        .filter(|(_, level)| *level > 1)
+        .collect()
+    }
+}
+
+fn read_u8(bytes: &mut &[u8]) -> Result<u8> {
+    let mut buf = [0; 1];
+    bytes
+        .read_exact(&mut buf)
+        .context("unexpected end of file")?;
+    Ok(buf[0])
+}
+
+fn read_u16(bytes: &mut &[u8]) -> Result<u16> {
+    let mut buf = [0; 2];
+    bytes
+        .read_exact(&mut buf)
+        .context("unexpected end of file")?;
+    Ok(u16::from_be_bytes(buf))
+}
+
+pub fn decode(mut bytes: &[u8], layout: Layout) -> Result<Vec<EquipmentEntry>> {
+    match layout {
+        Layout::Slots => Ok(bytes
+            .iter()
+            .enumerate()
+            .map(|(id, slot)| EquipmentEntry {
+                id: id as i32,
+                slot: i32::from(*slot),
+                details: None,
+            })
+            .collect()),
+        Layout::Apollo => {
+            let count = read_u16(&mut bytes)?;
+            let mut entries = Vec::with_capacity(count.into());
+            for id in 0..i32::from(count) {
+                let slot = i32::from(read_u8(&mut bytes)? as i8);
+                let details = if slot == NOT_EQUIPABLE {
+                    None
+                } else {
+                    let bytes = &mut bytes;
+                    Some(EquipmentDetails {
+                        two_handed: read_u8(bytes)? != 0,
+                        full_body: read_u8(bytes)? != 0,
+                        full_hat: read_u8(bytes)? != 0,
+                        full_mask: read_u8(bytes)? != 0,
+                        requirements: Requirements {
+                            attack: read_u8(bytes)?,
+                            strength: read_u8(bytes)?,
+                            defence: read_u8(bytes)?,
+                            ranged: read_u8(bytes)?,
+                            prayer: read_u8(bytes)?,
+                            magic: read_u8(bytes)?,
+                        },
+                    })
+                };
+                entries.push(EquipmentEntry { id, slot, details });
+            }
+            if !bytes.is_empty() {
+                bail!("{} unexpected bytes after {count} items", bytes.len());
+            }
+            Ok(entries)
+        }
+    }
+}
+
+/// encodes the entries, which must cover every id from 0 without gaps
+pub fn encode(entries: &[EquipmentEntry], layout: Layout) -> Result<Vec<u8>> {
+    let mut entries = entries.iter().collect::<Vec<_>>();
+    entries.sort_by_key(|entry| entry.id);
+    for (index, entry) in entries.iter().enumerate() {
+        if entry.id != index as i32 {
+            bail!(
+                "expected an entry for item {index} but found item {}",
+                entry.id
+            );
+        }
+    }
+
+    let mut bytes = Vec::new();
+    match layout {
+        Layout::Slots => {
+            for entry in entries {
+                let slot = u8::try_from(entry.slot).with_context(|| {
+                    format!("slot {} of item {} is not a byte", entry.slot, entry.id)
+                })?;
+                bytes.push(slot);
+            }
+        }
+        Layout::Apollo => {
+            let count = u16::try_from(entries.len())
+                .with_context(|| format!("{} items do not fit in a short", entries.len()))?;
+            bytes.extend(count.to_be_bytes());
+            for entry in entries {
+                let slot = i8::try_from(entry.slot).with_context(|| {
+                    format!("slot {} of item {} is not a byte", entry.slot, entry.id)
+                })?;
+                bytes.push(slot as u8);
+                if entry.slot == NOT_EQUIPABLE {
+                    continue;
+                }
+                let details = entry.details.clone().unwrap_or_default();
+                let requirements = &details.requirements;
+                bytes.extend([
+                    u8::from(details.two_handed),
+                    u8::from(details.full_body),
+                    u8::from(details.full_hat),
+                    u8::from(details.full_mask),
+                    requirements.attack,
+                    requirements.strength,
+                    requirements.defence,
+                    requirements.ranged,
+                    requirements.prayer,
+                    requirements.magic,
+                ]);
+            }
+        }
+    }
+    Ok(bytes)
+}
+
+pub fn load(path: &Path, layout: Layout) -> Result<Vec<EquipmentEntry>> {
+    let bytes = std::fs::read(path)
+        .with_context(|| format!("could not read equipment from {}", path.display()))?;
+    decode(&bytes, layout)
+        .with_context(|| format!("could not decode equipment from {}", path.display()))
+}
+
+pub fn save(path: &Path, entries: &[EquipmentEntry], layout: Layout) -> Result<()> {
+    let bytes = encode(entries, layout)
+        .with_context(|| format!("could not encode equipment for {}", path.display()))?;
+    std::fs::write(path, bytes)
+        .with_context(|| format!("could not write equipment to {}", path.display()))
+}
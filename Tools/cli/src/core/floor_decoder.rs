@@ -0,0 +1,70 @@
+use anyhow::{bail, Context, Result};
+use serde::Serialize;
+
+use super::{archive::CONFIG_ARCHIVE, buffer::Buffer, cache::IndexedFileSystem};
+
+/// the colour of overlays which are not drawn, leaving the underlay visible
+pub const HIDDEN_COLOUR: u32 = 0xFF00FF;
+
+/// an underlay or overlay as stored in the config archive's `flo.dat`
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct FloorDefinition {
+    pub id: i32,
+    pub name: Option<String>,
+    /// 24 bit rgb, unused if the floor is textured
+    pub colour: u32,
+    pub texture: Option<u8>,
+    /// the colour drawn on the minimap instead of `colour`
+    pub minimap_colour: Option<u32>,
+    pub occlude: bool,
+}
+
+impl FloorDefinition {
+    /// the colour to draw on a top down map
+    /// `None` for hidden floors and textured floors without a minimap colour
+    pub fn map_colour(&self) -> Option<u32> {
+        self.minimap_colour
+            .or((self.texture.is_none() && self.colour != HIDDEN_COLOUR).then_some(self.colour))
+    }
+}
+
+/// decodes every floor of `flo.dat`, which has no index file and stores the definitions back to back
+pub fn decode_all(cache: &IndexedFileSystem) -> Result<Vec<FloorDefinition>> {
+    let config = cache.archive(CONFIG_ARCHIVE)?;
+    let mut buffer = Buffer::new(config.entry("flo.dat")?);
+    let count = buffer.read_u16()?;
+    (0..i32::from(count))
+        .map(|id| decode(id, &mut buffer).with_context(|| format!("could not decode floor {id}")))
+        .collect()
+}
+
+/// decodes the opcodes of a single floor until the terminating 0
+pub fn decode(id: i32, buffer: &mut Buffer) -> Result<FloorDefinition> {
+    let mut floor = FloorDefinition {
+        id,
+        name: None,
+        colour: 0,
+        texture: None,
+        minimap_colour: None,
+        occlude: true,
+    };
+
+    loop {
+        let opcode = buffer.read_u8()?;
+        match opcode {
+            0 => return Ok(floor),
+            1 => floor.colour = buffer.read_u24()?,
+            2 => floor.texture = Some(buffer.read_u8()?),
+            // unused flag
+            3 => {}
+            5 => floor.occlude = false,
+            6 => floor.name = Some(buffer.read_string()?),
+            7 => floor.minimap_colour = Some(buffer.read_u24()?),
+            _ => bail!(
+                "unknown opcode {opcode} at position {}",
+                buffer.position() - 1
+            ),
+        }
+    }
+}
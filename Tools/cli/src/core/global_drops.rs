@@ -0,0 +1,34 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::json::lenient_i32;
+
+/// mirrors `com.rs2.util.GlobalDropData` as loaded from `data/cfg/globaldrops.json`
+/// these are ground items which respawn after being picked up
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GlobalDrop {
+    #[serde(deserialize_with = "lenient_i32")]
+    pub amount: i32,
+    pub item_x: i32,
+    pub id: i32,
+    pub item_y: i32,
+    /// most entries omit the height, which then defaults to 0
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub height: Option<i32>,
+}
+
+impl GlobalDrop {
+    pub fn height(&self) -> i32 {
+        self.height.unwrap_or(0)
+    }
+}
+
+pub fn load_all(path: &Path) -> Result<Vec<GlobalDrop>> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("could not read global drops from {}", path.display()))?;
+    serde_json::from_str(&contents)
+        .with_context(|| format!("could not parse global drops from {}", path.display()))
+}
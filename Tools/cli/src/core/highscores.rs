@@ -0,0 +1,195 @@
+use std::fmt::Write as _;
+
+use anyhow::Result;
+use itertools::Itertools;
+use serde::Serialize;
+
+use super::{
+    characters::{self, CharacterSave, SKILL_NAMES},
+    economy::{self, Valuation},
+};
+
+/// `HighscoresHandler` skips admins and developers
+pub const MIN_EXCLUDED_RIGHTS: i32 = 2;
+/// `HighscoresHandler` skips names starting with this character
+pub const EXCLUDED_NAME_PREFIX: char = '♥';
+
+/// the values a player is ranked by
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct PlayerStats {
+    pub name: String,
+    pub total_level: i32,
+    pub total_xp: i64,
+    /// the level for the experience of each skill, boosts and drains are ignored
+    pub levels: Vec<i32>,
+    pub xp: Vec<i32>,
+    pub wealth: i64,
+    pub damage: i32,
+}
+
+/// whether `HighscoresHandler` ranks the player, the name is the save's file name
+pub fn is_ranked(name: &str, save: &CharacterSave) -> Result<bool> {
+    Ok(save.rights()? < MIN_EXCLUDED_RIGHTS
+        && !save.is_bot()
+        && !name.starts_with(EXCLUDED_NAME_PREFIX))
+}
+
+pub fn player_stats(
+    name: &str,
+    save: &CharacterSave,
+    valuation: &Valuation,
+) -> Result<PlayerStats> {
+    let mut xp = vec![0; SKILL_NAMES.len()];
+    for skill in &save.skills {
+        if let Some(slot) = xp.get_mut(skill.skill) {
+            *slot = skill.xp;
+        }
+    }
+    // getLevelForXP treats negative experience as level 1
+    let levels = xp
+        .iter()
+        .map(|xp| characters::level_for_xp(*xp))
+        .collect_vec();
+    Ok(PlayerStats {
+        name: name.to_string(),
+        total_level: levels.iter().sum(),
+        total_xp: xp.iter().map(|xp| i64::from(*xp)).sum(),
+        levels,
+        xp,
+        wealth: economy::character_holdings(name, save, valuation)
+            .wealth
+            .total,
+        damage: save.int_property("global-damage")?.unwrap_or(0),
+    })
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct Rank {
+    pub rank: usize,
+    pub name: String,
+    /// the level for skill rankings and the total level for the overall ranking
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub level: Option<i32>,
+    pub value: i64,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct Ranking {
+    pub name: String,
+    /// what the value of each rank is
+    pub value: &'static str,
+    pub ranks: Vec<Rank>,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct Highscores {
+    pub generated: String,
+    pub players: usize,
+    pub rankings: Vec<Ranking>,
+}
+
+/// ranks the players overall, per skill, by wealth and by damage dealt, keeping the `top` of each
+pub fn rank(players: &[PlayerStats], generated: String, top: usize) -> Highscores {
+    let ranking =
+        |name: &str, value: &'static str, key: &dyn Fn(&PlayerStats) -> (Option<i32>, i64)| {
+            let ranks = players
+                .iter()
+                .map(|player| (player, key(player)))
+                .sorted_by(|(a, a_key), (b, b_key)| {
+                    b_key
+                        .0
+                        .cmp(&a_key.0)
+                        .then(b_key.1.cmp(&a_key.1))
+                        .then(a.name.cmp(&b.name))
+                })
+                .take(top)
+                .enumerate()
+                .map(|(index, (player, (level, value)))| Rank {
+                    rank: index + 1,
+                    name: player.name.clone(),
+                    level,
+                    value,
+                })
+                .collect();
+            Ranking {
+                name: name.to_string(),
+                value,
+                ranks,
+            }
+        };
+
+    let mut rankings = vec![ranking("overall", "xp", &|p| {
+        (Some(p.total_level), p.total_xp)
+    })];
+    for (skill, name) in SKILL_NAMES.iter().enumerate() {
+        rankings.push(ranking(name, "xp", &|p| {
+            (Some(p.levels[skill]), i64::from(p.xp[skill]))
+        }));
+    }
+    rankings.push(ranking("wealth", "coins", &|p| (None, p.wealth)));
+    rankings.push(ranking("damage", "damage", &|p| {
+        (None, i64::from(p.damage))
+    }));
+
+    Highscores {
+        generated,
+        players: players.len(),
+        rankings,
+    }
+}
+
+/// a static page with a table per ranking
+pub fn to_html(highscores: &Highscores) -> String {
+    let mut html = String::from(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>Highscores</title>\n\
+         <style>\nbody { font-family: sans-serif; background: #1f1a14; color: #e8d8b0; }\n\
+         section { display: inline-block; vertical-align: top; margin: 0 1em 1em 0; }\n\
+         table { border-collapse: collapse; }\n\
+         th, td { padding: 0.2em 0.6em; border-bottom: 1px solid #4a3f30; text-align: right; }\n\
+         td:nth-child(2), th:nth-child(2) { text-align: left; }\n</style>\n</head>\n<body>\n",
+    );
+    let _ = writeln!(html, "<h1>Highscores</h1>");
+    let _ = writeln!(
+        html,
+        "<p>{} players ranked, generated {}</p>",
+        highscores.players,
+        escape(&highscores.generated)
+    );
+    for ranking in &highscores.rankings {
+        let has_level = ranking.ranks.iter().any(|rank| rank.level.is_some());
+        let _ = writeln!(
+            html,
+            "<section>\n<h2>{}</h2>\n<table>",
+            escape(&ranking.name)
+        );
+        let level_header = if has_level { "<th>Level</th>" } else { "" };
+        let _ = writeln!(
+            html,
+            "<tr><th>Rank</th><th>Name</th>{level_header}<th>{}</th></tr>",
+            escape(ranking.value)
+        );
+        for rank in &ranking.ranks {
+            let level = rank
+                .level
+                .map(|level| format!("<td>{level}</td>"))
+                .unwrap_or_default();
+            let _ = writeln!(
+                html,
+                "<tr><td>{}</td><td>{}</td>{level}<td>{}</td></tr>",
+                rank.rank,
+                escape(&rank.name),
+                rank.value
+            );
+        }
+        let _ = writeln!(html, "</table>\n</section>");
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
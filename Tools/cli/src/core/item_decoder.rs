@@ -0,0 +1,195 @@
+use std::collections::BTreeMap;
+
+use anyhow::{bail, Result};
+use serde::Serialize;
+
+use super::{
+    archive::CONFIG_ARCHIVE, buffer::Buffer, cache::IndexedFileSystem,
+    item_definition::ItemDefinition, modify::ACTION_SLOTS,
+};
+
+/// decodes every item of the config archive's `obj.dat`, mirrors `ItemDefinitionDecoder`
+/// notes are left as stored in the cache, like the files in `data/item_definitions`
+pub fn decode_all(cache: &IndexedFileSystem) -> Result<Vec<ItemDefinition>> {
+    cache
+        .archive(CONFIG_ARCHIVE)?
+        .decode_definitions("obj", decode)
+}
+
+/// decodes the opcodes of a single item until the terminating 0
+pub fn decode(id: i32, buffer: &mut Buffer) -> Result<ItemDefinition> {
+    let mut item = ItemDefinition {
+        description: None,
+        ground_actions: Default::default(),
+        id,
+        inventory_actions: Default::default(),
+        members: false,
+        name: None,
+        note_graphic_id: None,
+        note_info_id: None,
+        stackable: false,
+        team: 0,
+        value: 1,
+        weight: None,
+        bonuses: None,
+    };
+
+    loop {
+        let opcode = buffer.read_u8()?;
+        match opcode {
+            0 => return Ok(item),
+            // model
+            1 => skip(buffer, 2)?,
+            2 => item.name = Some(buffer.read_string()?),
+            3 => item.description = Some(buffer.read_string()?),
+            // model zoom, rotation and offsets
+            4..=8 | 10 => skip(buffer, 2)?,
+            11 => item.stackable = true,
+            12 => item.value = buffer.read_i32()?,
+            16 => item.members = true,
+            // male and female models with offsets
+            23 | 25 => skip(buffer, 3)?,
+            24 | 26 => skip(buffer, 2)?,
+            30..=34 => {
+                let action = buffer.read_string()?;
+                let slot = usize::from(opcode - 30);
+                item.ground_actions[slot] =
+                    (!action.eq_ignore_ascii_case("hidden")).then_some(action);
+            }
+            35..=39 => {
+                let slot = usize::from(opcode - 35);
+                item.inventory_actions[slot] = Some(buffer.read_string()?);
+            }
+            // recoloured colours
+            40 => {
+                let colours = buffer.read_u8()?;
+                skip(buffer, usize::from(colours) * 4)?;
+            }
+            // extra and head models
+            78 | 79 | 90..=93 | 95 => skip(buffer, 2)?,
+            97 => item.note_info_id = Some(i32::from(buffer.read_u16()?)),
+            98 => item.note_graphic_id = Some(i32::from(buffer.read_u16()?)),
+            // stack variants
+            100..=109 => skip(buffer, 4)?,
+            // model scale
+            110..=112 => skip(buffer, 2)?,
+            // light ambience and diffusion
+            113 | 114 => skip(buffer, 1)?,
+            115 => item.team = i32::from(buffer.read_u8()?),
+            _ => bail!(
+                "unknown opcode {opcode} at position {}",
+                buffer.position() - 1
+            ),
+        }
+    }
+}
+
+fn skip(buffer: &mut Buffer, len: usize) -> Result<()> {
+    buffer.read_bytes(len).map(|_| ())
+}
+
+/// a field of an item which differs between `data/item_definitions` and the cache
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct ItemDifference {
+    pub item_id: i32,
+    pub field: String,
+    pub json: String,
+    pub cache: String,
+}
+
+impl std::fmt::Display for ItemDifference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "item {}: {} is {} in the JSON but {} in the cache",
+            self.item_id, self.field, self.json, self.cache
+        )
+    }
+}
+
+fn describe<T: std::fmt::Debug>(value: &Option<T>) -> String {
+    value
+        .as_ref()
+        .map_or("missing".to_string(), |value| format!("{value:?}"))
+}
+
+/// compares the name, actions, stackable and members flags, value and note ids of every item
+pub fn diff(json: &[ItemDefinition], cache: &[ItemDefinition]) -> Vec<ItemDifference> {
+    let mut pairs: BTreeMap<i32, (Option<&ItemDefinition>, Option<&ItemDefinition>)> =
+        BTreeMap::new();
+    for item in json {
+        pairs.entry(item.id).or_default().0 = Some(item);
+    }
+    for item in cache {
+        pairs.entry(item.id).or_default().1 = Some(item);
+    }
+
+    let mut differences = Vec::new();
+    for (item_id, pair) in pairs {
+        let mut push = |field: String, json: String, cache: String| {
+            if json != cache {
+                differences.push(ItemDifference {
+                    item_id,
+                    field,
+                    json,
+                    cache,
+                });
+            }
+        };
+
+        let (Some(json), Some(cache)) = pair else {
+            push(
+                "definition".to_string(),
+                describe(&pair.0.map(|_| "present")),
+                describe(&pair.1.map(|_| "present")),
+            );
+            continue;
+        };
+
+        push(
+            "name".to_string(),
+            describe(&json.name),
+            describe(&cache.name),
+        );
+        for slot in 0..ACTION_SLOTS {
+            push(
+                format!("groundActions[{slot}]"),
+                describe(&json.ground_actions[slot]),
+                describe(&cache.ground_actions[slot]),
+            );
+        }
+        for slot in 0..ACTION_SLOTS {
+            push(
+                format!("inventoryActions[{slot}]"),
+                describe(&json.inventory_actions[slot]),
+                describe(&cache.inventory_actions[slot]),
+            );
+        }
+        push(
+            "stackable".to_string(),
+            json.stackable.to_string(),
+            cache.stackable.to_string(),
+        );
+        push(
+            "members".to_string(),
+            json.members.to_string(),
+            cache.members.to_string(),
+        );
+        push(
+            "value".to_string(),
+            json.value.to_string(),
+            cache.value.to_string(),
+        );
+        push(
+            "noteGraphicId".to_string(),
+            describe(&json.note_graphic_id),
+            describe(&cache.note_graphic_id),
+        );
+        push(
+            "noteInfoId".to_string(),
+            describe(&json.note_info_id),
+            describe(&cache.note_info_id),
+        );
+    }
+    differences
+}
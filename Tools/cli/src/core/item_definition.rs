@@ -1,31 +1,131 @@
-use std::path::Path;
+use std::{collections::HashMap, path::Path};
 
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Clone, Debug, Hash, PartialEq, Eq)]
+use super::{
+    item_stats::{Bonuses, ItemStats},
+    json::to_gson_string,
+};
+
+/// mirrors `org.apollo.cache.def.ItemDefinition` as written to `data/item_definitions/{id}.json`
+/// fields are declared in the same order as the keys in the files so that saving is lossless
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
 pub struct ItemDefinition {
-    pub id: i32,
-    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
-    pub ground_actions: Option<[String; 5]>,
-    pub inventory_actions: Option<[String; 5]>,
+    pub ground_actions: [Option<String>; 5],
+    pub id: i32,
+    pub inventory_actions: [Option<String>; 5],
     pub members: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(with = "no_id_sentinel")]
     pub note_graphic_id: Option<i32>,
+    #[serde(with = "no_id_sentinel")]
     pub note_info_id: Option<i32>,
-    pub team: i32,
     pub stackable: bool,
+    pub team: i32,
     pub value: i32,
+    /// joined from `data/cfg/ItemDefinitions.json`, never written to the definition file
+    #[serde(skip)]
+    pub weight: Option<f64>,
+    /// joined from `data/cfg/ItemDefinitions.json`, never written to the definition file
+    #[serde(skip)]
+    pub bonuses: Option<Bonuses>,
+}
+
+impl ItemDefinition {
+    /// whether this definition is the noted counterpart of another item
+    pub fn is_note(&self) -> bool {
+        self.note_graphic_id.is_some()
+    }
+
+    /// the equipment bonuses the server uses, which are all 0 for items without bonuses
+    pub fn bonuses_or_default(&self) -> Bonuses {
+        self.bonuses.clone().unwrap_or_default()
+    }
+}
+
+/// the server writes `-1` for ids that are not set
+mod no_id_sentinel {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    const NO_ID: i32 = -1;
+
+    pub fn serialize<S: Serializer>(value: &Option<i32>, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i32(value.unwrap_or(NO_ID))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<i32>, D::Error> {
+        let value = i32::deserialize(deserializer)?;
+        Ok((value != NO_ID).then_some(value))
+    }
+}
+
+pub fn definition_path(dir: &Path, item_id: i32) -> std::path::PathBuf {
+    dir.join(format!("{item_id}.json"))
+}
+
+pub fn load(path: &Path) -> Result<ItemDefinition> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("could not read item definition {}", path.display()))?;
+    serde_json::from_str(&contents)
+        .with_context(|| format!("could not parse item definition {}", path.display()))
+}
+
+/// the id the bank holds the item as, which is the item a note represents,
+/// fails if the item or the item it represents has no definition
+pub fn bank_item_id(dir: &Path, item_id: i32) -> Result<i32> {
+    let path = definition_path(dir, item_id);
+    if !path.exists() {
+        bail!("item {item_id} does not exist in {}", dir.display());
+    }
+    let item = load(&path)?;
+    let Some(unnoted_id) = item.note_info_id.filter(|_| item.is_note()) else {
+        return Ok(item_id);
+    };
+    if !definition_path(dir, unnoted_id).exists() {
+        bail!("item {item_id} is a note of item {unnoted_id}, which does not exist");
+    }
+    Ok(unnoted_id)
 }
 
 pub fn load_all(dir: &Path) -> Result<Vec<ItemDefinition>> {
     let mut items: Vec<_> = Vec::new();
-    for entry in std::fs::read_dir(dir).unwrap() {
+    for entry in std::fs::read_dir(dir)
+        .with_context(|| format!("could not read item definitions from {}", dir.display()))?
+    {
         let path = entry?.path();
-        let contents = std::fs::read_to_string(path)?;
-        let item: ItemDefinition = serde_json::from_str(&contents)?;
-        items.push(item);
+        if path.extension().is_some_and(|ext| ext == "json") {
+            items.push(load(&path)?);
+        }
     }
 
     Ok(items)
 }
+
+/// sets the weight and bonuses of each item, later entries win like in `ItemDefinitions.init`
+pub fn join_stats(items: &mut [ItemDefinition], stats: &[ItemStats]) {
+    let stats: HashMap<i32, &ItemStats> = stats.iter().map(|stats| (stats.id, stats)).collect();
+    for item in items {
+        if let Some(stats) = stats.get(&item.id) {
+            item.weight = stats.weight;
+            item.bonuses.clone_from(&stats.bonuses);
+        }
+    }
+}
+
+/// serializes the definition in the same format the server's Gson writer uses
+pub fn to_json_string(item: &ItemDefinition) -> Result<String> {
+    to_gson_string(item)
+}
+
+pub fn save(dir: &Path, item: &ItemDefinition) -> Result<()> {
+    let path = definition_path(dir, item.id);
+    std::fs::write(&path, to_json_string(item)?)
+        .with_context(|| format!("could not write item definition {}", path.display()))
+}
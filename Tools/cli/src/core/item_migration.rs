@@ -0,0 +1,290 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    characters::{self, CharacterSave},
+    global_drops::GlobalDrop,
+    item_definition::ItemDefinition,
+    modify::PendingChange,
+    npc_drops::NpcDrops,
+    shops::Shop,
+};
+
+/// old item ids mapped to their replacements
+pub type ItemMapping = BTreeMap<i32, i32>;
+
+/// reads a mapping file in the form `{"1234": 5678}`
+pub fn load_mapping(path: &Path) -> Result<ItemMapping> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("could not read item mapping from {}", path.display()))?;
+    serde_json::from_str(&contents)
+        .with_context(|| format!("could not parse item mapping from {}", path.display()))
+}
+
+/// checks the mapping against the item definitions and adds the noted counterpart of every
+/// mapped item, which must then have a noted counterpart of its own
+pub fn with_noted_counterparts(
+    mapping: &ItemMapping,
+    items: &HashMap<i32, ItemDefinition>,
+) -> Result<ItemMapping> {
+    let notes: HashMap<i32, i32> = items
+        .values()
+        .filter(|item| item.is_note())
+        .filter_map(|item| Some((item.note_info_id?, item.id)))
+        .collect();
+
+    let mut full_mapping = mapping.clone();
+    for (&from, &to) in mapping {
+        if from == to {
+            bail!("item {from} is mapped to itself");
+        }
+        if !items.contains_key(&to) {
+            bail!("item {from} is mapped to item {to}, which does not exist");
+        }
+        let Some(&noted_from) = notes.get(&from) else {
+            continue;
+        };
+        if mapping.contains_key(&noted_from) {
+            continue;
+        }
+        let Some(&noted_to) = notes.get(&to) else {
+            bail!("item {from} has the noted counterpart {noted_from} but item {to} has none");
+        };
+        full_mapping.insert(noted_from, noted_to);
+    }
+    Ok(full_mapping)
+}
+
+/// an item reference which was rewritten
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct Touch {
+    pub location: String,
+    pub from: i32,
+    pub to: i32,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct FileMigration {
+    pub path: PathBuf,
+    pub touches: Vec<Touch>,
+    #[serde(skip)]
+    pub change: PendingChange,
+}
+
+/// rewrites the items of every slot in the character saves
+pub fn migrate_characters(
+    characters_dir: &Path,
+    mapping: &ItemMapping,
+) -> Result<Vec<FileMigration>> {
+    let mut migrations = Vec::new();
+    for (name, path) in characters::save_paths(characters_dir)? {
+        let original = std::fs::read_to_string(&path)
+            .with_context(|| format!("could not read character from {}", path.display()))?;
+        let mut save = CharacterSave::parse(&original)
+            .with_context(|| format!("could not parse character from {}", path.display()))?;
+
+        let mut touches = Vec::new();
+        let mut migrate = |container: &str, slot: usize, item_id: &mut i32| {
+            if let Some(&to) = mapping.get(item_id) {
+                touches.push(Touch {
+                    location: format!("{container} slot {slot}"),
+                    from: *item_id,
+                    to,
+                });
+                *item_id = to;
+            }
+        };
+        for slot in &mut save.equipment {
+            migrate("equipment", slot.slot, &mut slot.item_id);
+        }
+        for slot in &mut save.inventory {
+            migrate("inventory", slot.slot, &mut slot.item_id);
+        }
+        for slot in &mut save.bank {
+            migrate("bank", slot.slot, &mut slot.item_id);
+        }
+        if touches.is_empty() {
+            continue;
+        }
+
+        let mut bank_items = save
+            .bank
+            .iter()
+            .map(|slot| slot.item_id)
+            .collect::<Vec<_>>();
+        bank_items.sort_unstable();
+        if bank_items.windows(2).any(|pair| pair[0] == pair[1]) {
+            log::warn!("{name} now has more than one bank slot holding the same item");
+        }
+
+        migrations.push(FileMigration {
+            path: path.clone(),
+            touches,
+            change: PendingChange {
+                path,
+                original,
+                modified: save.to_save_string(),
+            },
+        });
+    }
+    Ok(migrations)
+}
+
+pub fn migrate_npc_drops(path: &Path, mapping: &ItemMapping) -> Result<Option<FileMigration>> {
+    migrate_json(path, "item_id", mapping, |before: &Vec<NpcDrops>, after| {
+        let mut touches = Vec::new();
+        for (npc, migrated) in before.iter().zip(after) {
+            for (index, (drop, migrated)) in npc.items.iter().zip(&migrated.items).enumerate() {
+                touches.extend(touch(
+                    format!("npc {} drop {index}", npc.id),
+                    drop.item_id,
+                    migrated.item_id,
+                ));
+            }
+        }
+        touches
+    })
+}
+
+pub fn migrate_shops(path: &Path, mapping: &ItemMapping) -> Result<Option<FileMigration>> {
+    migrate_json(path, "itemId", mapping, |before: &Vec<Shop>, after| {
+        let mut touches = Vec::new();
+        for (shop, migrated) in before.iter().zip(after) {
+            for (index, (item, migrated)) in shop.items.iter().zip(&migrated.items).enumerate() {
+                touches.extend(touch(
+                    format!("shop {} item {index}", shop.id),
+                    item.item_id,
+                    migrated.item_id,
+                ));
+            }
+        }
+        touches
+    })
+}
+
+pub fn migrate_global_drops(path: &Path, mapping: &ItemMapping) -> Result<Option<FileMigration>> {
+    migrate_json(path, "id", mapping, |before: &Vec<GlobalDrop>, after| {
+        before
+            .iter()
+            .zip(after)
+            .filter_map(|(drop, migrated)| {
+                touch(
+                    format!(
+                        "global drop at {},{},{}",
+                        drop.item_x,
+                        drop.item_y,
+                        drop.height()
+                    ),
+                    drop.id,
+                    migrated.id,
+                )
+            })
+            .collect()
+    })
+}
+
+/// rewrites the `(id, name)` tuples of every list in `Tools/cli/data/item_ids`
+pub fn migrate_item_id_lists(
+    item_ids_dir: &Path,
+    mapping: &ItemMapping,
+    items: &HashMap<i32, ItemDefinition>,
+) -> Result<Vec<FileMigration>> {
+    let tuple = Regex::new(r#"\[\s*(-?\d+)\s*,\s*"((?:[^"\\]|\\.)*)"\s*\]"#)?;
+    let mut paths = std::fs::read_dir(item_ids_dir)
+        .with_context(|| format!("could not read {}", item_ids_dir.display()))?
+        .map(|entry| Ok(entry?.path()))
+        .collect::<Result<Vec<_>>>()?;
+    paths.retain(|path| path.extension().is_some_and(|ext| ext == "json"));
+    paths.sort();
+
+    let mut migrations = Vec::new();
+    for path in paths {
+        let original = std::fs::read_to_string(&path)
+            .with_context(|| format!("could not read {}", path.display()))?;
+        let mut touches = Vec::new();
+        let mut index = 0;
+        let modified = tuple.replace_all(&original, |caps: &Captures| {
+            let entry = index;
+            index += 1;
+            let from: i32 = caps[1].parse().unwrap_or(-1);
+            let Some(&to) = mapping.get(&from) else {
+                return caps[0].to_string();
+            };
+            touches.push(Touch {
+                location: format!("entry {entry}"),
+                from,
+                to,
+            });
+            let name = items
+                .get(&to)
+                .and_then(|item| item.name.as_deref())
+                .unwrap_or_default();
+            format!("[{to}, {}]", serde_json::Value::from(name))
+        });
+        if touches.is_empty() {
+            continue;
+        }
+        let modified = modified.into_owned();
+        serde_json::from_str::<Vec<(i32, String)>>(&modified)
+            .with_context(|| format!("could not parse the migrated {}", path.display()))?;
+        migrations.push(FileMigration {
+            path: path.clone(),
+            touches,
+            change: PendingChange {
+                path,
+                original,
+                modified,
+            },
+        });
+    }
+    Ok(migrations)
+}
+
+fn touch(location: String, from: i32, to: i32) -> Option<Touch> {
+    (from != to).then_some(Touch { location, from, to })
+}
+
+/// replaces the integer values of `key` in place, which keeps the file as the server wrote it,
+/// then reports the touched slots by comparing the file before and after
+fn migrate_json<T: for<'de> Deserialize<'de>>(
+    path: &Path,
+    key: &str,
+    mapping: &ItemMapping,
+    touches: impl Fn(&T, &T) -> Vec<Touch>,
+) -> Result<Option<FileMigration>> {
+    let original = std::fs::read_to_string(path)
+        .with_context(|| format!("could not read {}", path.display()))?;
+    let value = Regex::new(&format!(r#"("{}"\s*:\s*"?)(-?\d+)"#, regex::escape(key)))?;
+    let modified = value
+        .replace_all(&original, |caps: &Captures| {
+            match caps[2].parse().ok().and_then(|id: i32| mapping.get(&id)) {
+                Some(to) => format!("{}{to}", &caps[1]),
+                None => caps[0].to_string(),
+            }
+        })
+        .into_owned();
+    if modified == original {
+        return Ok(None);
+    }
+
+    let parse = |contents: &str| {
+        serde_json::from_str::<T>(contents)
+            .with_context(|| format!("could not parse {}", path.display()))
+    };
+    let touches = touches(&parse(&original)?, &parse(&modified)?);
+    Ok(Some(FileMigration {
+        path: path.to_path_buf(),
+        touches,
+        change: PendingChange {
+            path: path.to_path_buf(),
+            original,
+            modified,
+        },
+    }))
+}
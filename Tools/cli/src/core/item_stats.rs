@@ -0,0 +1,83 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// mirrors `ItemDefinitions.ItemData` as loaded from `data/cfg/ItemDefinitions.json`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ItemStats {
+    pub id: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weight: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bonuses: Option<Bonuses>,
+}
+
+/// mirrors `ItemDefinitions.Bonuses`, missing bonuses default to 0
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Bonuses {
+    pub attack_stab: i32,
+    pub attack_slash: i32,
+    pub attack_crush: i32,
+    pub attack_magic: i32,
+    pub attack_range: i32,
+    pub defence_stab: i32,
+    pub defence_slash: i32,
+    pub defence_crush: i32,
+    pub defence_magic: i32,
+    pub defence_range: i32,
+    pub strength_bonus: i32,
+    pub prayer_bonus: i32,
+}
+
+impl Bonuses {
+    /// the names of the bonuses as they appear in the file
+    pub const NAMES: [&'static str; 12] = [
+        "attackStab",
+        "attackSlash",
+        "attackCrush",
+        "attackMagic",
+        "attackRange",
+        "defenceStab",
+        "defenceSlash",
+        "defenceCrush",
+        "defenceMagic",
+        "defenceRange",
+        "strengthBonus",
+        "prayerBonus",
+    ];
+
+    /// the bonuses in the same order as [`Bonuses::NAMES`] and `ItemDefinitions.Bonuses.getBonuses`
+    pub fn values(&self) -> [i32; 12] {
+        [
+            self.attack_stab,
+            self.attack_slash,
+            self.attack_crush,
+            self.attack_magic,
+            self.attack_range,
+            self.defence_stab,
+            self.defence_slash,
+            self.defence_crush,
+            self.defence_magic,
+            self.defence_range,
+            self.strength_bonus,
+            self.prayer_bonus,
+        ]
+    }
+
+    /// looks up a bonus by its name in the file
+    pub fn get(&self, name: &str) -> Option<i32> {
+        Self::NAMES
+            .iter()
+            .position(|bonus| *bonus == name)
+            .map(|index| self.values()[index])
+    }
+}
+
+pub fn load_all(path: &Path) -> Result<Vec<ItemStats>> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("could not read item stats from {}", path.display()))?;
+    serde_json::from_str(&contents)
+        .with_context(|| format!("could not parse item stats from {}", path.display()))
+}
@@ -0,0 +1,159 @@
+use std::io::{self, Write};
+
+use anyhow::Result;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::ser::{Formatter, PrettyFormatter};
+
+/// a pretty printer matching `new GsonBuilder().setPrettyPrinting().create()`
+/// Gson escapes HTML-sensitive characters by default, so a plain serde_json pretty printer
+/// would write e.g. `\u0027` as `'` and produce noisy diffs against the server's data
+struct GsonFormatter<'a> {
+    inner: PrettyFormatter<'a>,
+}
+
+impl GsonFormatter<'_> {
+    fn new() -> Self {
+        Self {
+            inner: PrettyFormatter::with_indent(b"  "),
+        }
+    }
+}
+
+impl Formatter for GsonFormatter<'_> {
+    fn begin_array<W: ?Sized + Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.inner.begin_array(writer)
+    }
+
+    fn end_array<W: ?Sized + Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.inner.end_array(writer)
+    }
+
+    fn begin_array_value<W: ?Sized + Write>(
+        &mut self,
+        writer: &mut W,
+        first: bool,
+    ) -> io::Result<()> {
+        self.inner.begin_array_value(writer, first)
+    }
+
+    fn end_array_value<W: ?Sized + Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.inner.end_array_value(writer)
+    }
+
+    fn begin_object<W: ?Sized + Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.inner.begin_object(writer)
+    }
+
+    fn end_object<W: ?Sized + Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.inner.end_object(writer)
+    }
+
+    fn begin_object_key<W: ?Sized + Write>(
+        &mut self,
+        writer: &mut W,
+        first: bool,
+    ) -> io::Result<()> {
+        self.inner.begin_object_key(writer, first)
+    }
+
+    fn begin_object_value<W: ?Sized + Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.inner.begin_object_value(writer)
+    }
+
+    fn end_object_value<W: ?Sized + Write>(&mut self, writer: &mut W) -> io::Result<()> {
+        self.inner.end_object_value(writer)
+    }
+
+    fn write_string_fragment<W: ?Sized + Write>(
+        &mut self,
+        writer: &mut W,
+        fragment: &str,
+    ) -> io::Result<()> {
+        let mut start = 0;
+        for (i, c) in fragment.char_indices() {
+            let escaped = match c {
+                '<' | '>' | '&' | '=' | '\'' | '\u{2028}' | '\u{2029}' => {
+                    format!("\\u{:04x}", c as u32)
+                }
+                _ => continue,
+            };
+            writer.write_all(&fragment.as_bytes()[start..i])?;
+            writer.write_all(escaped.as_bytes())?;
+            start = i + c.len_utf8();
+        }
+        writer.write_all(&fragment.as_bytes()[start..])
+    }
+}
+
+/// serializes the value exactly as the server's Gson pretty printer would
+pub fn to_gson_string<T: Serialize>(value: &T) -> Result<String> {
+    let mut buffer = Vec::new();
+    let mut serializer = serde_json::Serializer::with_formatter(&mut buffer, GsonFormatter::new());
+    value.serialize(&mut serializer)?;
+    Ok(String::from_utf8(buffer)?)
+}
+
+/// deserializes an integer the way Gson does, which also accepts numeric strings such as `"5"`
+pub fn lenient_i32<'de, D: Deserializer<'de>>(deserializer: D) -> Result<i32, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum NumberOrString {
+        Number(i32),
+        String(String),
+    }
+
+    match NumberOrString::deserialize(deserializer)? {
+        NumberOrString::Number(n) => Ok(n),
+        NumberOrString::String(s) => s.trim().parse().map_err(serde::de::Error::custom),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Entry {
+        id: i32,
+        name: String,
+        tags: Vec<String>,
+    }
+
+    #[test]
+    fn writes_gson_layout_and_escapes() -> Result<()> {
+        let entries = vec![Entry {
+            id: 4151,
+            name: "Abyssal whip's <edge> & hilt=1".to_string(),
+            tags: vec!["melee".to_string()],
+        }];
+        let json = to_gson_string(&entries)?;
+        assert_eq!(
+            json,
+            r#"[
+  {
+    "id": 4151,
+    "name": "Abyssal whip\u0027s \u003cedge\u003e \u0026 hilt\u003d1",
+    "tags": [
+      "melee"
+    ]
+  }
+]"#
+        );
+        assert_eq!(serde_json::from_str::<Vec<Entry>>(&json)?, entries);
+        Ok(())
+    }
+
+    #[test]
+    fn reads_numeric_strings_as_integers() -> Result<()> {
+        #[derive(Deserialize)]
+        struct Lenient {
+            #[serde(deserialize_with = "lenient_i32")]
+            value: i32,
+        }
+        let lenient: Lenient = serde_json::from_str(r#"{"value": " 5"}"#)?;
+        assert_eq!(lenient.value, 5);
+        let lenient: Lenient = serde_json::from_str(r#"{"value": -3}"#)?;
+        assert_eq!(lenient.value, -3);
+        Ok(())
+    }
+}
@@ -0,0 +1,232 @@
+use std::{collections::BTreeMap, io::Read};
+
+use anyhow::{bail, Context, Result};
+use flate2::read::GzDecoder;
+use serde::Serialize;
+
+use super::{
+    buffer::Buffer,
+    cache::{IndexedFileSystem, MAP_INDEX},
+    spawns::region_base,
+};
+
+/// the file in the archive index containing the versions archive with `map_index`
+pub const VERSIONS_ARCHIVE: usize = 5;
+/// the width and length of a region in tiles
+pub const MAP_WIDTH: i32 = 64;
+pub const MAP_PLANES: i32 = 4;
+
+/// tile attribute of tiles which can not be walked on
+pub const BLOCKED_TILE: u8 = 1;
+/// tile attribute on plane 1 which moves the tiles and objects above it down by one plane
+pub const BRIDGE_TILE: u8 = 2;
+
+const LOWEST_CONTINUED_TYPE: u8 = 2;
+const MINIMUM_OVERLAY_TYPE: u8 = 49;
+const LOWEST_ATTRIBUTES_TYPE: u8 = MINIMUM_OVERLAY_TYPE + 1;
+const MINIMUM_ATTRIBUTES_TYPE: u8 = 81;
+const ORIENTATION_COUNT: u8 = 4;
+
+/// the cache files of a region, see `org.apollo.cache.map.MapIndex`
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MapIndex {
+    pub region_id: i32,
+    pub terrain_file: usize,
+    pub objects_file: usize,
+    pub members: bool,
+}
+
+/// decodes `map_index` of the versions archive, mirrors `MapIndexDecoder`
+pub fn decode_index(cache: &IndexedFileSystem) -> Result<BTreeMap<i32, MapIndex>> {
+    let versions = cache.archive(VERSIONS_ARCHIVE)?;
+    let data = versions.entry("map_index")?;
+    let mut buffer = Buffer::new(data);
+
+    let mut indices = BTreeMap::new();
+    for _ in 0..data.len() / 7 {
+        let index = MapIndex {
+            region_id: i32::from(buffer.read_u16()?),
+            terrain_file: usize::from(buffer.read_u16()?),
+            objects_file: usize::from(buffer.read_u16()?),
+            members: buffer.read_u8()? == 1,
+        };
+        indices.insert(index.region_id, index);
+    }
+    Ok(indices)
+}
+
+/// a tile of the terrain, see `org.apollo.cache.map.Tile`
+/// heights are not kept since nothing in rs-cli draws them
+#[derive(Serialize, Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Tile {
+    /// the floor id plus one, 0 if there is no overlay
+    pub overlay: u8,
+    pub overlay_type: u8,
+    pub overlay_orientation: u8,
+    /// flags such as [`BLOCKED_TILE`] and [`BRIDGE_TILE`]
+    pub attributes: u8,
+    /// the floor id plus one, 0 if there is no underlay
+    pub underlay: u8,
+}
+
+/// an object placed in a region, see `org.apollo.cache.map.MapObject`
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MapObject {
+    pub id: i32,
+    /// the coordinate inside of the region
+    pub local_x: i32,
+    pub local_y: i32,
+    pub plane: i32,
+    /// the `ObjectType`, such as 0 for straight walls or 10 for interactable objects
+    pub object_type: u8,
+    /// 0 to 3 for west, north, east and south
+    pub orientation: u8,
+}
+
+/// the decoded terrain and objects of a 64x64 region
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Region {
+    pub index: MapIndex,
+    tiles: Vec<Tile>,
+    pub objects: Vec<MapObject>,
+}
+
+impl Region {
+    /// the coordinate of the south west corner of the region
+    pub fn base(&self) -> (i32, i32) {
+        region_base(self.index.region_id)
+    }
+
+    pub fn tile(&self, plane: i32, local_x: i32, local_y: i32) -> &Tile {
+        &self.tiles[tile_offset(plane, local_x, local_y)]
+    }
+
+    /// whether the tiles above the coordinate are moved down by one plane
+    pub fn is_bridge(&self, local_x: i32, local_y: i32) -> bool {
+        self.tile(1, local_x, local_y).attributes & BRIDGE_TILE != 0
+    }
+
+    /// the plane a tile or object is shown on once bridges are applied
+    pub fn visible_plane(&self, plane: i32, local_x: i32, local_y: i32) -> i32 {
+        if plane > 0 && self.is_bridge(local_x, local_y) {
+            plane - 1
+        } else {
+            plane
+        }
+    }
+
+    /// the tile shown on the plane once bridges are applied
+    pub fn visible_tile(&self, plane: i32, local_x: i32, local_y: i32) -> &Tile {
+        if plane + 1 < MAP_PLANES && self.is_bridge(local_x, local_y) {
+            self.tile(plane + 1, local_x, local_y)
+        } else {
+            self.tile(plane, local_x, local_y)
+        }
+    }
+}
+
+fn tile_offset(plane: i32, local_x: i32, local_y: i32) -> usize {
+    ((plane * MAP_WIDTH + local_x) * MAP_WIDTH + local_y) as usize
+}
+
+/// reads and decodes the terrain and object files of a region
+pub fn load_region(cache: &IndexedFileSystem, index: &MapIndex) -> Result<Region> {
+    let terrain = degzip(&cache.read(MAP_INDEX, index.terrain_file)?)
+        .with_context(|| format!("could not read terrain of region {}", index.region_id))?;
+    let objects = degzip(&cache.read(MAP_INDEX, index.objects_file)?)
+        .with_context(|| format!("could not read objects of region {}", index.region_id))?;
+    Ok(Region {
+        index: *index,
+        tiles: decode_terrain(&terrain)
+            .with_context(|| format!("could not decode terrain of region {}", index.region_id))?,
+        objects: decode_objects(&objects)
+            .with_context(|| format!("could not decode objects of region {}", index.region_id))?,
+    })
+}
+
+/// see `CompressionUtil.degzip`
+fn degzip(compressed: &[u8]) -> Result<Vec<u8>> {
+    let mut decompressed = Vec::new();
+    GzDecoder::new(compressed)
+        .read_to_end(&mut decompressed)
+        .context("could not decompress gzip data")?;
+    Ok(decompressed)
+}
+
+/// decodes the tiles of every plane, mirrors `MapFileDecoder`
+pub fn decode_terrain(data: &[u8]) -> Result<Vec<Tile>> {
+    let mut buffer = Buffer::new(data);
+    let mut tiles = vec![Tile::default(); (MAP_PLANES * MAP_WIDTH * MAP_WIDTH) as usize];
+    for plane in 0..MAP_PLANES {
+        for x in 0..MAP_WIDTH {
+            for y in 0..MAP_WIDTH {
+                tiles[tile_offset(plane, x, y)] = decode_tile(&mut buffer)?;
+            }
+        }
+    }
+    if buffer.remaining() > 0 {
+        bail!("{} unexpected bytes after the terrain", buffer.remaining());
+    }
+    Ok(tiles)
+}
+
+fn decode_tile(buffer: &mut Buffer) -> Result<Tile> {
+    let mut tile = Tile::default();
+    loop {
+        let tile_type = buffer.read_u8()?;
+        match tile_type {
+            // a generated height
+            0 => return Ok(tile),
+            // an explicit height
+            1 => {
+                buffer.read_u8()?;
+                return Ok(tile);
+            }
+            LOWEST_CONTINUED_TYPE..=MINIMUM_OVERLAY_TYPE => {
+                tile.overlay = buffer.read_u8()?;
+                tile.overlay_type = (tile_type - LOWEST_CONTINUED_TYPE) / ORIENTATION_COUNT;
+                // the java decoder misses the parentheses here
+                tile.overlay_orientation = (tile_type - LOWEST_CONTINUED_TYPE) % ORIENTATION_COUNT;
+            }
+            LOWEST_ATTRIBUTES_TYPE..=MINIMUM_ATTRIBUTES_TYPE => {
+                tile.attributes = tile_type - MINIMUM_OVERLAY_TYPE
+            }
+            _ => tile.underlay = tile_type - MINIMUM_ATTRIBUTES_TYPE,
+        }
+    }
+}
+
+/// decodes the objects of a region, mirrors `MapObjectsDecoder`
+pub fn decode_objects(data: &[u8]) -> Result<Vec<MapObject>> {
+    let mut buffer = Buffer::new(data);
+    let mut objects = Vec::new();
+    let mut id = -1;
+    loop {
+        let id_offset = buffer.read_smart()?;
+        if id_offset == 0 {
+            return Ok(objects);
+        }
+        id += i32::from(id_offset);
+
+        let mut packed = 0;
+        loop {
+            let position_offset = buffer.read_smart()?;
+            if position_offset == 0 {
+                break;
+            }
+            packed += i32::from(position_offset) - 1;
+            let attributes = buffer.read_u8()?;
+            objects.push(MapObject {
+                id,
+                local_x: packed >> 6 & 0x3F,
+                local_y: packed & 0x3F,
+                plane: packed >> 12 & 0x3,
+                object_type: attributes >> 2,
+                orientation: attributes & 0x3,
+            });
+        }
+    }
+}
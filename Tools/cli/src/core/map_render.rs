@@ -0,0 +1,321 @@
+use std::{collections::HashMap, fs::File, io::BufWriter, path::Path};
+
+use anyhow::{bail, Context, Result};
+
+use super::{
+    floor_decoder::{FloorDefinition, HIDDEN_COLOUR},
+    map::{Region, MAP_WIDTH},
+    object_decoder::ObjectDefinition,
+    spawns::{region_id, Area},
+};
+
+pub const WATER_COLOUR: u32 = 0x2A4C7A;
+pub const WALL_COLOUR: u32 = 0xEEEEEE;
+/// walls with actions, which are mostly doors, like on the minimap
+pub const INTERACTIVE_WALL_COLOUR: u32 = 0xCC0000;
+pub const NPC_COLOUR: u32 = 0xFFFF00;
+pub const GROUND_ITEM_COLOUR: u32 = 0xFF3030;
+pub const DOOR_COLOUR: u32 = 0x00E0E0;
+
+/// the most pixels an image may have, about 300 MB of rgb data
+pub const MAX_PIXELS: u64 = 100_000_000;
+
+const STRAIGHT_WALL: u8 = 0;
+const DIAGONAL_CORNER_WALL: u8 = 1;
+const CORNER_WALL: u8 = 2;
+const RECTANGULAR_CORNER_WALL: u8 = 3;
+const DIAGONAL_WALL: u8 = 9;
+const INTERACTABLE: u8 = 10;
+const DIAGONAL_INTERACTABLE: u8 = 11;
+
+/// a coloured dot drawn on top of the map
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Marker {
+    pub x: i32,
+    pub y: i32,
+    pub height: i32,
+    pub colour: u32,
+}
+
+/// an rgb image
+pub struct MapImage {
+    pub width: u32,
+    pub height: u32,
+    pixels: Vec<u8>,
+}
+
+impl MapImage {
+    pub fn new(width: u32, height: u32) -> Result<Self> {
+        let pixels = u64::from(width) * u64::from(height);
+        if pixels > MAX_PIXELS {
+            bail!("a {width}x{height} image is larger than {MAX_PIXELS} pixels");
+        }
+        let Some(size) = usize::try_from(pixels * 3).ok() else {
+            bail!("a {width}x{height} image does not fit in memory");
+        };
+        Ok(Self {
+            width,
+            height,
+            pixels: vec![0; size],
+        })
+    }
+
+    fn offset(&self, x: i64, y: i64) -> Option<usize> {
+        ((0..i64::from(self.width)).contains(&x) && (0..i64::from(self.height)).contains(&y))
+            .then(|| ((y as usize) * self.width as usize + x as usize) * 3)
+    }
+
+    pub fn set(&mut self, x: i64, y: i64, colour: u32) {
+        if let Some(offset) = self.offset(x, y) {
+            self.pixels[offset..offset + 3].copy_from_slice(&colour.to_be_bytes()[1..]);
+        }
+    }
+
+    pub fn fill(&mut self, x: i64, y: i64, width: i64, height: i64, colour: u32) {
+        for py in y..y + height {
+            for px in x..x + width {
+                self.set(px, py, colour);
+            }
+        }
+    }
+
+    /// multiplies every channel of the pixels by `factor`
+    pub fn darken(&mut self, x: i64, y: i64, width: i64, height: i64, factor: f64) {
+        for py in y..y + height {
+            for px in x..x + width {
+                if let Some(offset) = self.offset(px, py) {
+                    for channel in &mut self.pixels[offset..offset + 3] {
+                        *channel = (f64::from(*channel) * factor) as u8;
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn save_png(&self, path: &Path) -> Result<()> {
+        let file =
+            File::create(path).with_context(|| format!("could not create {}", path.display()))?;
+        let mut encoder = png::Encoder::new(BufWriter::new(file), self.width, self.height);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&self.pixels))
+            .with_context(|| format!("could not write {}", path.display()))
+    }
+}
+
+/// everything needed to draw a part of the map
+pub struct MapRenderer<'a> {
+    /// the decoded regions by region id, regions which are missing are drawn black
+    pub regions: &'a HashMap<i32, Region>,
+    pub floors: &'a [FloorDefinition],
+    pub objects: &'a [ObjectDefinition],
+    /// the size of a tile in pixels
+    pub scale: u32,
+}
+
+/// the width and height in pixels of an image of the area, failing for images larger than
+/// `MAX_PIXELS`
+pub fn image_size(area: &Area, scale: u32) -> Result<(u32, u32)> {
+    let side = |min: i32, max: i32| {
+        u32::try_from(i64::from(max) - i64::from(min) + 1)
+            .ok()
+            .and_then(|tiles| tiles.checked_mul(scale))
+    };
+    let (Some(width), Some(height)) = (side(area.min_x, area.max_x), side(area.min_y, area.max_y))
+    else {
+        bail!("the area is too large to render at scale {scale}");
+    };
+    if u64::from(width) * u64::from(height) > MAX_PIXELS {
+        bail!("a {width}x{height} image is larger than {MAX_PIXELS} pixels, render a smaller area or scale");
+    }
+    Ok((width, height))
+}
+
+impl MapRenderer<'_> {
+    /// draws the area of the plane with north at the top, then the markers on the plane
+    pub fn render(&self, area: &Area, plane: i32, markers: &[Marker]) -> Result<MapImage> {
+        let scale = i64::from(self.scale);
+        let (width, height) = image_size(area, self.scale)?;
+        let mut image = MapImage::new(width, height)?;
+        // the pixel of the north west corner of a tile
+        let origin = |x: i32, y: i32| {
+            (
+                i64::from(x - area.min_x) * scale,
+                i64::from(area.max_y - y) * scale,
+            )
+        };
+
+        for x in area.min_x..=area.max_x {
+            for y in area.min_y..=area.max_y {
+                let Some(region) = self.regions.get(&region_id(x, y)) else {
+                    continue;
+                };
+                let tile = region.visible_tile(plane, x & (MAP_WIDTH - 1), y & (MAP_WIDTH - 1));
+                let colour = self
+                    .floor_colour(tile.overlay)
+                    .or_else(|| self.floor_colour(tile.underlay))
+                    .unwrap_or(0);
+                let (px, py) = origin(x, y);
+                image.fill(px, py, scale, scale, colour);
+            }
+        }
+
+        // solid objects first so walls are drawn on top of them
+        let mut placed = Vec::new();
+        for region in self.regions.values() {
+            let (base_x, base_y) = region.base();
+            for object in &region.objects {
+                let (x, y) = (base_x + object.local_x, base_y + object.local_y);
+                if area.contains(x, y)
+                    && region.visible_plane(object.plane, object.local_x, object.local_y) == plane
+                {
+                    if let Some(definition) = self.objects.get(object.id as usize) {
+                        placed.push((x, y, object, definition));
+                    }
+                }
+            }
+        }
+        placed.sort_by_key(|(_, _, object, _)| object.object_type < INTERACTABLE);
+
+        let thickness = (scale / 4).max(1);
+        for (x, y, object, definition) in placed {
+            let (px, py) = origin(x, y);
+            let colour = if definition.menu_actions().next().is_some() {
+                INTERACTIVE_WALL_COLOUR
+            } else {
+                WALL_COLOUR
+            };
+            match object.object_type {
+                STRAIGHT_WALL => draw_edge(
+                    &mut image,
+                    px,
+                    py,
+                    scale,
+                    thickness,
+                    object.orientation,
+                    colour,
+                ),
+                CORNER_WALL => {
+                    draw_edge(
+                        &mut image,
+                        px,
+                        py,
+                        scale,
+                        thickness,
+                        object.orientation,
+                        colour,
+                    );
+                    draw_edge(
+                        &mut image,
+                        px,
+                        py,
+                        scale,
+                        thickness,
+                        (object.orientation + 1) % 4,
+                        colour,
+                    );
+                }
+                DIAGONAL_CORNER_WALL | RECTANGULAR_CORNER_WALL => {
+                    let (cx, cy) = corner(px, py, scale, thickness, object.orientation);
+                    image.fill(cx, cy, thickness, thickness, colour);
+                }
+                DIAGONAL_WALL => {
+                    for step in 0..scale {
+                        // orientations 0 and 2 run from south west to north east
+                        let dx = if object.orientation % 2 == 0 {
+                            step
+                        } else {
+                            scale - 1 - step
+                        };
+                        image.fill(px + dx, py + scale - 1 - step, thickness, 1, colour);
+                    }
+                }
+                INTERACTABLE | DIAGONAL_INTERACTABLE if definition.solid => {
+                    let (width, length) = if object.orientation % 2 == 1 {
+                        (definition.length, definition.width)
+                    } else {
+                        (definition.width, definition.length)
+                    };
+                    // the object extends north from its south west tile
+                    let top = py - i64::from(length - 1) * scale;
+                    image.darken(
+                        px,
+                        top,
+                        i64::from(width) * scale,
+                        i64::from(length) * scale,
+                        0.6,
+                    );
+                }
+                _ => {}
+            }
+        }
+
+        let size = (scale / 2).max(2);
+        for marker in markers {
+            if marker.height == plane && area.contains(marker.x, marker.y) {
+                let (px, py) = origin(marker.x, marker.y);
+                let offset = (scale - size) / 2;
+                image.fill(px + offset, py + offset, size, size, marker.colour);
+            }
+        }
+
+        Ok(image)
+    }
+
+    /// the colour of a floor id plus one as stored in tiles
+    fn floor_colour(&self, floor: u8) -> Option<u32> {
+        let floor = self.floors.get(usize::from(floor).checked_sub(1)?)?;
+        floor.map_colour().or_else(|| {
+            floor
+                .texture
+                .filter(|_| floor.colour != HIDDEN_COLOUR)
+                .map(texture_colour)
+        })
+    }
+}
+
+/// roughly the average colour of the textures used by floors
+fn texture_colour(texture: u8) -> u32 {
+    match texture {
+        1 | 17 | 24 | 25 => WATER_COLOUR,
+        // planks
+        3 => 0x6B4A2A,
+        // bricks
+        2 | 23 => 0x7A4A3A,
+        // lava
+        31 => 0xC04010,
+        // marble, stone and pebbles
+        _ => 0x808080,
+    }
+}
+
+/// draws the west, north, east or south edge of a tile
+fn draw_edge(
+    image: &mut MapImage,
+    px: i64,
+    py: i64,
+    scale: i64,
+    thickness: i64,
+    orientation: u8,
+    colour: u32,
+) {
+    match orientation {
+        0 => image.fill(px, py, thickness, scale, colour),
+        1 => image.fill(px, py, scale, thickness, colour),
+        2 => image.fill(px + scale - thickness, py, thickness, scale, colour),
+        _ => image.fill(px, py + scale - thickness, scale, thickness, colour),
+    }
+}
+
+/// the pixel of the north west, north east, south east or south west corner of a tile
+fn corner(px: i64, py: i64, scale: i64, thickness: i64, orientation: u8) -> (i64, i64) {
+    let far = scale - thickness;
+    match orientation {
+        0 => (px, py),
+        1 => (px + far, py),
+        2 => (px + far, py + far),
+        _ => (px, py + far),
+    }
+}
@@ -1,3 +1,35 @@
+pub mod accounts;
+pub mod anonymize;
+pub mod archive;
+pub mod buffer;
+pub mod bulk;
+pub mod cache;
+pub mod characters;
+pub mod collision;
+pub mod doors;
+pub mod drop_simulator;
+pub mod economy;
+pub mod equipment;
+pub mod floor_decoder;
+pub mod global_drops;
+pub mod highscores;
+pub mod item_decoder;
 pub mod item_definition;
+pub mod item_migration;
+pub mod item_stats;
+pub mod json;
 pub mod log;
+pub mod map;
+pub mod map_render;
+pub mod moderation;
 pub mod modify;
+pub mod npc_data;
+pub mod npc_decoder;
+pub mod npc_definition;
+pub mod npc_drops;
+pub mod npc_migration;
+pub mod object_decoder;
+pub mod save_check;
+pub mod shops;
+pub mod spawns;
+pub mod validate;
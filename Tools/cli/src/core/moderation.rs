@@ -0,0 +1,390 @@
+use std::{
+    collections::HashMap,
+    net::IpAddr,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use anyhow::{bail, Context, Result};
+use ipnet::{IpNet, Ipv4AddrRange, Ipv6AddrRange};
+use serde::{Deserialize, Serialize};
+
+/// the most addresses a single CIDR range may expand to
+pub const MAX_RANGE_ADDRESSES: u128 = 65_536;
+
+/// the hand-edited lists read by `Connection` and `HostBlacklist`
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum ModerationList {
+    BannedUsers,
+    MutedUsers,
+    BannedIps,
+    MutedIps,
+    BlacklistedHosts,
+}
+
+impl ModerationList {
+    pub const ALL: [ModerationList; 5] = [
+        ModerationList::BannedUsers,
+        ModerationList::MutedUsers,
+        ModerationList::BannedIps,
+        ModerationList::MutedIps,
+        ModerationList::BlacklistedHosts,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ModerationList::BannedUsers => "banned-users",
+            ModerationList::MutedUsers => "muted-users",
+            ModerationList::BannedIps => "banned-ips",
+            ModerationList::MutedIps => "muted-ips",
+            ModerationList::BlacklistedHosts => "blacklisted-hosts",
+        }
+    }
+
+    pub fn parse(s: &str) -> Result<ModerationList> {
+        ModerationList::ALL
+            .into_iter()
+            .find(|list| list.name() == s)
+            .with_context(|| {
+                let names = ModerationList::ALL.map(|list| list.name()).join(", ");
+                format!("unknown list {s}, expected one of {names}")
+            })
+    }
+
+    pub fn path(&self, data_dir: &Path) -> PathBuf {
+        let bans = data_dir.join("bans");
+        match self {
+            ModerationList::BannedUsers => bans.join("UsersBanned.txt"),
+            ModerationList::MutedUsers => bans.join("UsersMuted.txt"),
+            ModerationList::BannedIps => bans.join("IpsBanned.txt"),
+            ModerationList::MutedIps => bans.join("IpsMuted.txt"),
+            ModerationList::BlacklistedHosts => data_dir.join("blacklist.txt"),
+        }
+    }
+
+    fn holds_ips(&self) -> bool {
+        matches!(self, ModerationList::BannedIps | ModerationList::MutedIps)
+    }
+
+    /// normalizes an entry the way the server compares it
+    /// names and hosts are lowercased, ips are written like `InetAddress.getHostAddress`
+    pub fn normalize(&self, entry: &str) -> Result<String> {
+        let entry = entry.split_whitespace().collect::<Vec<_>>().join(" ");
+        if entry.is_empty() {
+            bail!("empty entry");
+        }
+        match self {
+            ModerationList::BlacklistedHosts => {
+                return Ok(entry.trim_end_matches('.').to_lowercase())
+            }
+            ModerationList::BannedUsers | ModerationList::MutedUsers => {
+                return Ok(entry.to_lowercase())
+            }
+            ModerationList::BannedIps | ModerationList::MutedIps => {}
+        }
+        let address: IpAddr = entry
+            .parse()
+            .with_context(|| format!("{entry} is not an ip address"))?;
+        Ok(host_address(address))
+    }
+
+    /// the entries the server needs for a rule, ip ranges are expanded to every address
+    pub fn expand(&self, rule: &str) -> Result<Vec<String>> {
+        if !self.holds_ips() || !rule.contains('/') {
+            return Ok(vec![self.normalize(rule)?]);
+        }
+        let range: IpNet = rule
+            .trim()
+            .parse()
+            .with_context(|| format!("{rule} is not a CIDR range"))?;
+        let size = 1u128 << (range.max_prefix_len() - range.prefix_len()).min(127);
+        if size > MAX_RANGE_ADDRESSES {
+            bail!("{range} holds {size} addresses, more than the limit of {MAX_RANGE_ADDRESSES}");
+        }
+        // every address of the range, including the network and broadcast addresses
+        let addresses: Vec<IpAddr> = match range {
+            IpNet::V4(range) => Ipv4AddrRange::new(range.network(), range.broadcast())
+                .map(IpAddr::V4)
+                .collect(),
+            IpNet::V6(range) => Ipv6AddrRange::new(range.network(), range.broadcast())
+                .map(IpAddr::V6)
+                .collect(),
+        };
+        Ok(addresses.into_iter().map(host_address).collect())
+    }
+
+    /// the rule as it is recorded in the sidecar, ranges keep their prefix
+    pub fn normalize_rule(&self, rule: &str) -> Result<String> {
+        if !self.holds_ips() || !rule.contains('/') {
+            return self.normalize(rule);
+        }
+        let range: IpNet = rule
+            .trim()
+            .parse()
+            .with_context(|| format!("{rule} is not a CIDR range"))?;
+        Ok(range.trunc().to_string())
+    }
+}
+
+/// formats the address like Java's `InetAddress.getHostAddress`,
+/// which writes every ipv6 group without compressing zeros
+fn host_address(address: IpAddr) -> String {
+    match address {
+        IpAddr::V4(address) => address.to_string(),
+        IpAddr::V6(address) => address
+            .segments()
+            .iter()
+            .map(|segment| format!("{segment:x}"))
+            .collect::<Vec<_>>()
+            .join(":"),
+    }
+}
+
+/// the entries of a list file, normalized with duplicates removed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ListFile {
+    pub list: ModerationList,
+    pub path: PathBuf,
+    pub entries: Vec<String>,
+}
+
+impl ListFile {
+    pub fn load(data_dir: &Path, list: ModerationList) -> Result<ListFile> {
+        let path = list.path(data_dir);
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => {
+                return Err(e).with_context(|| format!("could not read {}", path.display()));
+            }
+        };
+        let mut file = ListFile {
+            list,
+            path,
+            entries: Vec::new(),
+        };
+        for line in contents.lines().filter(|line| !line.trim().is_empty()) {
+            let entry = list.normalize(line).unwrap_or_else(|e| {
+                log::warn!("{}: {e:#}, keeping it as written", file.path.display());
+                line.trim().to_string()
+            });
+            file.insert(entry);
+        }
+        Ok(file)
+    }
+
+    pub fn contains(&self, entry: &str) -> bool {
+        self.entries.iter().any(|e| e == entry)
+    }
+
+    /// appends the entry unless it is already listed, returning whether it was added
+    pub fn insert(&mut self, entry: String) -> bool {
+        if self.contains(&entry) {
+            return false;
+        }
+        self.entries.push(entry);
+        true
+    }
+
+    /// returns whether the entry was listed
+    pub fn remove(&mut self, entry: &str) -> bool {
+        let count = self.entries.len();
+        self.entries.retain(|e| e != entry);
+        self.entries.len() != count
+    }
+
+    pub fn to_file_string(&self) -> String {
+        self.entries
+            .iter()
+            .map(|entry| format!("{entry}\n"))
+            .collect()
+    }
+
+    pub fn save(&self) -> Result<()> {
+        std::fs::write(&self.path, self.to_file_string())
+            .with_context(|| format!("could not write {}", self.path.display()))
+    }
+}
+
+/// an entry added by rs-cli, kept in a sidecar file since the server's lists hold nothing but entries
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct Rule {
+    pub list: ModerationList,
+    /// the normalized entry or CIDR range
+    pub rule: String,
+    /// an RFC 3339 timestamp, as is the expiry
+    pub added: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// the entries the rule added to the list, only these are removed when the rule expires
+    /// or is removed, so entries listed before it stay listed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub entries: Vec<String>,
+}
+
+impl Rule {
+    pub fn expiry(&self) -> Result<Option<SystemTime>> {
+        self.expires
+            .as_deref()
+            .map(|expires| {
+                humantime::parse_rfc3339(expires)
+                    .with_context(|| format!("could not parse expiry {expires} of {}", self.rule))
+            })
+            .transpose()
+    }
+
+    pub fn is_expired(&self, now: SystemTime) -> Result<bool> {
+        Ok(self.expiry()?.is_some_and(|expiry| expiry <= now))
+    }
+}
+
+/// adds the entries of the rule missing from the list and records the rule, replacing an
+/// earlier rule for the same entry or range, returns the number of entries added
+pub fn add_rule(file: &mut ListFile, rules: &mut Vec<Rule>, mut rule: Rule) -> Result<usize> {
+    let added = file
+        .list
+        .expand(&rule.rule)?
+        .into_iter()
+        .filter(|entry| file.insert(entry.clone()))
+        .collect::<Vec<_>>();
+    let count = added.len();
+    if let Some(index) = rules
+        .iter()
+        .position(|r| r.list == rule.list && r.rule == rule.rule)
+    {
+        rule.entries = rules.remove(index).entries;
+    }
+    rule.entries.extend(added);
+    rules.push(rule);
+    Ok(count)
+}
+
+/// removes the entries the rule added from the list, entries one of the remaining rules
+/// covers stay listed and become that rule's entries, returns the number of entries removed
+pub fn release_rule(file: &mut ListFile, rule: &Rule, remaining: &mut [Rule]) -> Result<usize> {
+    let mut covering = HashMap::new();
+    for (index, other) in remaining.iter().enumerate() {
+        if other.list == file.list {
+            for entry in file.list.expand(&other.rule)? {
+                covering.entry(entry).or_insert(index);
+            }
+        }
+    }
+    let mut removed = 0;
+    for entry in &rule.entries {
+        match covering.get(entry) {
+            Some(&index) => {
+                if !remaining[index].entries.contains(entry) {
+                    remaining[index].entries.push(entry.clone());
+                }
+            }
+            None => {
+                if file.remove(entry) {
+                    removed += 1;
+                }
+            }
+        }
+    }
+    Ok(removed)
+}
+
+pub fn sidecar_path(data_dir: &Path) -> PathBuf {
+    data_dir.join("bans").join("moderation.json")
+}
+
+pub fn load_rules(data_dir: &Path) -> Result<Vec<Rule>> {
+    let path = sidecar_path(data_dir);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let contents = std::fs::read_to_string(&path)
+        .with_context(|| format!("could not read {}", path.display()))?;
+    serde_json::from_str(&contents).with_context(|| format!("could not parse {}", path.display()))
+}
+
+pub fn save_rules(data_dir: &Path, rules: &[Rule]) -> Result<()> {
+    let path = sidecar_path(data_dir);
+    let mut json = serde_json::to_string_pretty(rules)?;
+    json.push('\n');
+    std::fs::write(&path, json).with_context(|| format!("could not write {}", path.display()))
+}
+
+pub fn timestamp(time: SystemTime) -> String {
+    humantime::format_rfc3339_seconds(time).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list_file(list: ModerationList, entries: &[&str]) -> ListFile {
+        ListFile {
+            list,
+            path: PathBuf::new(),
+            entries: entries.iter().map(|entry| entry.to_string()).collect(),
+        }
+    }
+
+    fn rule(list: ModerationList, rule: &str) -> Rule {
+        Rule {
+            list,
+            rule: rule.to_string(),
+            added: "2024-01-01T00:00:00Z".to_string(),
+            expires: Some("2024-01-02T00:00:00Z".to_string()),
+            reason: None,
+            entries: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn releasing_a_rule_keeps_entries_listed_before_it() -> Result<()> {
+        let list = ModerationList::BannedUsers;
+        let mut file = list_file(list, &["alice"]);
+        let mut rules = Vec::new();
+        assert_eq!(add_rule(&mut file, &mut rules, rule(list, "alice"))?, 0);
+        assert_eq!(add_rule(&mut file, &mut rules, rule(list, "bob"))?, 1);
+
+        for rule in std::mem::take(&mut rules) {
+            release_rule(&mut file, &rule, &mut [])?;
+        }
+        assert_eq!(file.entries, ["alice"]);
+        Ok(())
+    }
+
+    #[test]
+    fn releasing_a_range_keeps_addresses_listed_before_or_covered_by_another_rule() -> Result<()> {
+        let list = ModerationList::BannedIps;
+        let mut file = list_file(list, &["10.0.0.1"]);
+        let mut rules = Vec::new();
+        assert_eq!(
+            add_rule(&mut file, &mut rules, rule(list, "10.0.0.0/30"))?,
+            3
+        );
+        assert_eq!(add_rule(&mut file, &mut rules, rule(list, "10.0.0.2"))?, 0);
+
+        let range = rules.remove(0);
+        assert_eq!(release_rule(&mut file, &range, &mut rules)?, 2);
+        assert_eq!(file.entries, ["10.0.0.1", "10.0.0.2"]);
+
+        let address = rules.remove(0);
+        assert_eq!(address.entries, ["10.0.0.2"]);
+        assert_eq!(release_rule(&mut file, &address, &mut rules)?, 1);
+        assert_eq!(file.entries, ["10.0.0.1"]);
+        Ok(())
+    }
+
+    #[test]
+    fn adding_a_rule_again_keeps_the_entries_it_added() -> Result<()> {
+        let list = ModerationList::MutedUsers;
+        let mut file = list_file(list, &[]);
+        let mut rules = Vec::new();
+        add_rule(&mut file, &mut rules, rule(list, "bob"))?;
+        add_rule(&mut file, &mut rules, rule(list, "bob"))?;
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].entries, ["bob"]);
+        Ok(())
+    }
+}
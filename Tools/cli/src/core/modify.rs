@@ -1,13 +1,177 @@
-use std::path::Path;
+use std::{
+    ffi::OsString,
+    path::{Path, PathBuf},
+};
 
-use anyhow::Result;
+use anyhow::{bail, Context, Result};
+use similar::TextDiff;
 
+use super::item_definition::{self, ItemDefinition};
+
+/// the number of ground and inventory action slots on an item
+pub const ACTION_SLOTS: usize = 5;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Modification {
+    Name(Option<String>),
+    Description(Option<String>),
+    Value(i32),
+    Members(bool),
     Stackable(bool),
+    Team(i32),
+    NoteGraphicId(Option<i32>),
+    NoteInfoId(Option<i32>),
+    GroundAction { slot: usize, action: Option<String> },
+    InventoryAction { slot: usize, action: Option<String> },
+}
+
+impl Modification {
+    pub fn apply(&self, item: &mut ItemDefinition) -> Result<()> {
+        match self {
+            Modification::Name(name) => item.name.clone_from(name),
+            Modification::Description(description) => item.description.clone_from(description),
+            Modification::Value(value) => item.value = *value,
+            Modification::Members(members) => item.members = *members,
+            Modification::Stackable(stackable) => item.stackable = *stackable,
+            Modification::Team(team) => item.team = *team,
+            Modification::NoteGraphicId(id) => item.note_graphic_id = *id,
+            Modification::NoteInfoId(id) => item.note_info_id = *id,
+            Modification::GroundAction { slot, action } => {
+                *action_slot(&mut item.ground_actions, *slot)? = action.clone()
+            }
+            Modification::InventoryAction { slot, action } => {
+                *action_slot(&mut item.inventory_actions, *slot)? = action.clone()
+            }
+        }
+        Ok(())
+    }
+}
+
+fn action_slot(
+    actions: &mut [Option<String>; ACTION_SLOTS],
+    slot: usize,
+) -> Result<&mut Option<String>> {
+    match actions.get_mut(slot) {
+        Some(action) => Ok(action),
+        None => bail!("action slot {slot} is out of range, expected 0..{ACTION_SLOTS}"),
+    }
 }
 
-pub fn modify(dir: &Path, item_id: u32, modification: &Modification) -> Result<()> {
-    let item_path = dir.join(format!("{item_id}.json"));
+/// the contents of a definition file before and after a set of modifications
+/// nothing is written to disk until [`PendingChange::write`] is called
+#[derive(Clone, Debug)]
+pub struct PendingChange {
+    pub path: PathBuf,
+    pub original: String,
+    pub modified: String,
+}
+
+impl PendingChange {
+    pub fn is_noop(&self) -> bool {
+        self.original == self.modified
+    }
+
+    pub fn unified_diff(&self) -> String {
+        let path = self.path.display().to_string();
+        TextDiff::from_lines(&self.original, &self.modified)
+            .unified_diff()
+            .header(&path, &path)
+            .to_string()
+    }
+
+    pub fn write(&self) -> Result<()> {
+        write_atomically(&self.path, &self.modified)
+    }
+}
 
+/// writes the contents to a temporary file next to the path and renames it over the path,
+/// so a failed write leaves the file as it was
+pub fn write_atomically(path: &Path, contents: &str) -> Result<()> {
+    let file_name = path
+        .file_name()
+        .with_context(|| format!("{} has no file name", path.display()))?;
+    let mut temp_name = OsString::from(".");
+    temp_name.push(file_name);
+    temp_name.push(".tmp");
+    let temp_path = path.with_file_name(temp_name);
+    let result = std::fs::write(&temp_path, contents)
+        .with_context(|| format!("could not write {}", temp_path.display()))
+        .and_then(|()| {
+            std::fs::rename(&temp_path, path)
+                .with_context(|| format!("could not write {}", path.display()))
+        });
+    if result.is_err() {
+        let _ = std::fs::remove_file(&temp_path);
+    }
+    result
+}
+
+/// writes every change, restoring the files already written if any write fails
+pub fn write_all(changes: &[PendingChange]) -> Result<()> {
+    for (index, change) in changes.iter().enumerate() {
+        if let Err(e) = change.write() {
+            for written in &changes[..index] {
+                if let Err(restore_error) = write_atomically(&written.path, &written.original) {
+                    log::error!(
+                        "could not restore {}: {restore_error}",
+                        written.path.display()
+                    );
+                }
+            }
+            return Err(e.context("the files already written were restored"));
+        }
+    }
     Ok(())
 }
+
+/// applies the modifications to the definition of `item_id` in `dir`
+pub fn modify(dir: &Path, item_id: i32, modifications: &[Modification]) -> Result<PendingChange> {
+    let item_path = item_definition::definition_path(dir, item_id);
+    let original = std::fs::read_to_string(&item_path)
+        .with_context(|| format!("could not read {}", item_path.display()))?;
+    let mut item: ItemDefinition = serde_json::from_str(&original)
+        .with_context(|| format!("could not parse {}", item_path.display()))?;
+
+    for modification in modifications {
+        modification
+            .apply(&mut item)
+            .with_context(|| format!("could not modify item {item_id}"))?;
+    }
+
+    Ok(PendingChange {
+        path: item_path,
+        original,
+        modified: item_definition::to_json_string(&item)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn failed_write_restores_the_files_already_written() -> Result<()> {
+        let dir = std::env::temp_dir().join(format!("rs-cli-write-all-{}", std::process::id()));
+        std::fs::create_dir_all(&dir)?;
+        let written = dir.join("0.json");
+        std::fs::write(&written, "original")?;
+        let changes = [
+            PendingChange {
+                path: written.clone(),
+                original: "original".to_string(),
+                modified: "modified".to_string(),
+            },
+            PendingChange {
+                path: dir.join("missing").join("1.json"),
+                original: String::new(),
+                modified: "modified".to_string(),
+            },
+        ];
+
+        assert!(write_all(&changes).is_err());
+        assert_eq!(std::fs::read_to_string(&written)?, "original");
+        assert_eq!(std::fs::read_dir(&dir)?.count(), 1);
+        std::fs::remove_dir_all(&dir)?;
+        Ok(())
+    }
+}
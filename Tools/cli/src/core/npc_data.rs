@@ -0,0 +1,89 @@
+use std::{collections::HashMap, path::Path};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// mirrors `com.rs2.util.NpcData` as loaded from `data/cfg/npc.json`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct NpcData {
+    pub hitpoints: i32,
+    pub name: String,
+    pub combat: i32,
+    pub id: i32,
+    /// the text before the entry where it is not the usual newline and indentation, such as a
+    /// blank line, kept so that saving writes the list back the way it was read
+    #[serde(skip)]
+    pub text_before: Option<String>,
+}
+
+/// the text before each entry of the list
+const ENTRY_TEXT: &str = "\n  ";
+
+pub fn load_all(path: &Path) -> Result<Vec<NpcData>> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("could not read npc list from {}", path.display()))?;
+    from_json_str(&contents)
+        .with_context(|| format!("could not parse npc list from {}", path.display()))
+}
+
+pub fn from_json_str(contents: &str) -> Result<Vec<NpcData>> {
+    let mut npcs: Vec<NpcData> = serde_json::from_str(contents)?;
+    record_text_before(contents, &mut npcs)?;
+    Ok(npcs)
+}
+
+/// the entry as it is laid out inside the list
+fn entry_string(npc: &NpcData) -> Result<String> {
+    Ok(serde_json::to_string_pretty(npc)?.replace('\n', ENTRY_TEXT))
+}
+
+/// finds each entry in the file in turn, stopping at the first one laid out differently
+fn record_text_before(contents: &str, npcs: &mut [NpcData]) -> Result<()> {
+    let mut rest = contents.trim_start().strip_prefix('[').unwrap_or(contents);
+    for npc in npcs {
+        let entry = entry_string(npc)?;
+        let Some(start) = rest.find(&entry) else {
+            break;
+        };
+        let text = rest[..start].strip_prefix(',').unwrap_or(&rest[..start]);
+        if !text.chars().all(char::is_whitespace) {
+            break;
+        }
+        if text != ENTRY_TEXT {
+            npc.text_before = Some(text.to_string());
+        }
+        rest = &rest[start + entry.len()..];
+    }
+    Ok(())
+}
+
+/// serializes the npc list in the same layout as `data/cfg/npc.json`
+pub fn to_json_string(npcs: &[NpcData]) -> Result<String> {
+    if npcs.is_empty() {
+        return Ok("[]\n".to_string());
+    }
+    let mut json = String::from("[");
+    for (index, npc) in npcs.iter().enumerate() {
+        if index > 0 {
+            json.push(',');
+        }
+        json.push_str(npc.text_before.as_deref().unwrap_or(ENTRY_TEXT));
+        json.push_str(&entry_string(npc)?);
+    }
+    json.push_str("\n]\n");
+    Ok(json)
+}
+
+pub fn save_all(path: &Path, npcs: &[NpcData]) -> Result<()> {
+    std::fs::write(path, to_json_string(npcs)?)
+        .with_context(|| format!("could not write npc list to {}", path.display()))
+}
+
+/// maps npc ids to names, the server's `getNpcListName` uses the first entry for duplicate ids
+pub fn names_by_id(npcs: &[NpcData]) -> HashMap<i32, String> {
+    let mut names = HashMap::new();
+    for npc in npcs {
+        names.entry(npc.id).or_insert_with(|| npc.name.clone());
+    }
+    names
+}
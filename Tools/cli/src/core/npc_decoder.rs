@@ -0,0 +1,106 @@
+use anyhow::{bail, Result};
+use serde::Serialize;
+
+use super::{archive::CONFIG_ARCHIVE, buffer::Buffer, cache::IndexedFileSystem};
+
+/// the number of right click options of an npc
+pub const INTERACTION_SLOTS: usize = 5;
+
+/// an npc as stored in the cache, see `org.apollo.cache.def.NpcDefinition`
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheNpcDefinition {
+    pub id: i32,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub size: i32,
+    /// `None` for npcs without a combat level
+    pub combat_level: Option<i32>,
+    pub interactions: [Option<String>; INTERACTION_SLOTS],
+    pub stand_animation: i32,
+    pub walk_animation: i32,
+    pub walk_back_animation: i32,
+    pub walk_left_animation: i32,
+    pub walk_right_animation: i32,
+}
+
+/// decodes every npc of the config archive's `npc.dat`, mirrors `NpcDefinitionDecoder`
+pub fn decode_all(cache: &IndexedFileSystem) -> Result<Vec<CacheNpcDefinition>> {
+    cache
+        .archive(CONFIG_ARCHIVE)?
+        .decode_definitions("npc", decode)
+}
+
+/// decodes the opcodes of a single npc until the terminating 0
+pub fn decode(id: i32, buffer: &mut Buffer) -> Result<CacheNpcDefinition> {
+    let mut npc = CacheNpcDefinition {
+        id,
+        name: None,
+        description: None,
+        size: 1,
+        combat_level: None,
+        interactions: Default::default(),
+        stand_animation: -1,
+        walk_animation: -1,
+        walk_back_animation: -1,
+        walk_left_animation: -1,
+        walk_right_animation: -1,
+    };
+
+    loop {
+        let opcode = buffer.read_u8()?;
+        match opcode {
+            0 => return Ok(npc),
+            // models
+            1 | 60 => {
+                let models = buffer.read_u8()?;
+                skip(buffer, usize::from(models) * 2)?;
+            }
+            2 => npc.name = Some(buffer.read_string()?),
+            3 => npc.description = Some(buffer.read_string()?),
+            12 => npc.size = i32::from(buffer.read_i8()?),
+            13 => npc.stand_animation = i32::from(buffer.read_i16()?),
+            14 => npc.walk_animation = i32::from(buffer.read_i16()?),
+            17 => {
+                npc.walk_animation = i32::from(buffer.read_i16()?);
+                npc.walk_back_animation = i32::from(buffer.read_i16()?);
+                npc.walk_left_animation = i32::from(buffer.read_i16()?);
+                npc.walk_right_animation = i32::from(buffer.read_i16()?);
+            }
+            30..=39 => {
+                let action = buffer.read_string()?;
+                let slot = usize::from(opcode - 30);
+                let Some(interaction) = npc.interactions.get_mut(slot) else {
+                    bail!("interaction slot {slot} is out of range");
+                };
+                *interaction = (action != "hidden").then_some(action);
+            }
+            // recoloured colours
+            40 => {
+                let colours = buffer.read_u8()?;
+                skip(buffer, usize::from(colours) * 4)?;
+            }
+            // minimap visibility, render priority and clickability
+            93 | 99 | 107 => {}
+            95 => npc.combat_level = Some(i32::from(buffer.read_i16()?)),
+            // model scale, head icon and rotation
+            90..=92 | 97 | 98 | 102 | 103 => skip(buffer, 2)?,
+            // light ambience and diffusion
+            100 | 101 => skip(buffer, 1)?,
+            // morphisms
+            106 => {
+                skip(buffer, 4)?;
+                let count = buffer.read_u8()?;
+                skip(buffer, (usize::from(count) + 1) * 2)?;
+            }
+            _ => bail!(
+                "unknown opcode {opcode} at position {}",
+                buffer.position() - 1
+            ),
+        }
+    }
+}
+
+fn skip(buffer: &mut Buffer, len: usize) -> Result<()> {
+    buffer.read_bytes(len).map(|_| ())
+}
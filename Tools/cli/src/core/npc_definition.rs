@@ -0,0 +1,392 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    path::Path,
+    str::FromStr,
+};
+
+use anyhow::{bail, Context, Result};
+use quick_xml::{
+    escape::partial_escape,
+    events::{BytesStart, Event},
+    Reader,
+};
+use serde::{Deserialize, Serialize};
+
+/// mirrors `com.rs2.game.npcs.NPCDefinition` as loaded by XStream from `data/cfg/npcDefinitions.xml`
+/// fields are declared in the same order as the elements in the file so that saving keeps the order
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct NpcDefinition {
+    pub id: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub examine: Option<String>,
+    pub combat: i32,
+    pub size: i32,
+    pub attackable: bool,
+    pub aggressive: bool,
+    pub retreats: bool,
+    pub poisonous: bool,
+    /// the number of game ticks before the npc respawns
+    pub respawn: i32,
+    pub max_hit: i32,
+    pub hitpoints: i32,
+    /// the time between attacks in milliseconds
+    pub attack_speed: i32,
+    pub attack_anim: i32,
+    pub defence_anim: i32,
+    pub death_anim: i32,
+    pub attack_bonus: i32,
+    pub defence_melee: i32,
+    pub defence_range: i32,
+    pub defence_mage: i32,
+    #[serde(default, skip_serializing_if = "Layout::is_usual")]
+    pub layout: Layout,
+}
+
+/// where a definition differs from the usual layout of the file, so that saving writes it back
+/// the way it was read
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Layout {
+    /// elements the definition does not have, XStream keeps the initial value for these
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub missing: Vec<String>,
+    /// the text before a tag where it is not the usual newline and indentation, keyed by the
+    /// tag's name with a leading `/` for closing tags
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub text_before: BTreeMap<String, String>,
+}
+
+impl Layout {
+    pub fn is_usual(&self) -> bool {
+        self == &Layout::default()
+    }
+
+    fn text<'a>(&'a self, tag: &str, usual: &'a str) -> &'a str {
+        self.text_before.get(tag).map_or(usual, String::as_str)
+    }
+
+    fn record_text(&mut self, tag: &str, usual: &str, text: &mut String) {
+        if text != usual {
+            self.text_before.insert(tag.to_string(), text.clone());
+        }
+        text.clear();
+    }
+}
+
+impl Default for NpcDefinition {
+    /// the field initializers of the java class, XStream keeps these for missing elements
+    fn default() -> Self {
+        Self {
+            id: 0,
+            name: None,
+            examine: None,
+            combat: 0,
+            size: 1,
+            attackable: false,
+            aggressive: false,
+            retreats: false,
+            poisonous: false,
+            respawn: 0,
+            max_hit: 0,
+            hitpoints: 1,
+            attack_speed: 4000,
+            attack_anim: 422,
+            defence_anim: 404,
+            death_anim: 2304,
+            attack_bonus: 20,
+            defence_melee: 20,
+            defence_range: 20,
+            defence_mage: 20,
+            layout: Layout::default(),
+        }
+    }
+}
+
+const LIST_ELEMENT: &str = "list";
+const DEFINITION_ELEMENT: &str = "npcDefinition";
+const DEFINITION_END: &str = "/npcDefinition";
+
+/// the text before each kind of tag in the file
+const DEFINITION_TEXT: &str = "\n ";
+const FIELD_TEXT: &str = "\n   ";
+const DEFINITION_END_TEXT: &str = "\n  ";
+
+impl NpcDefinition {
+    fn set_field(&mut self, element: &str, text: &str) -> Result<()> {
+        fn parse<T: FromStr>(element: &str, text: &str) -> Result<T>
+        where
+            T::Err: std::error::Error + Send + Sync + 'static,
+        {
+            text.trim()
+                .parse()
+                .with_context(|| format!("invalid value {text:?} for <{element}>"))
+        }
+
+        match element {
+            "id" => self.id = parse(element, text)?,
+            "name" => self.name = Some(text.to_string()),
+            "examine" => self.examine = Some(text.to_string()),
+            "combat" => self.combat = parse(element, text)?,
+            "size" => self.size = parse(element, text)?,
+            "attackable" => self.attackable = parse(element, text)?,
+            "aggressive" => self.aggressive = parse(element, text)?,
+            "retreats" => self.retreats = parse(element, text)?,
+            "poisonous" => self.poisonous = parse(element, text)?,
+            "respawn" => self.respawn = parse(element, text)?,
+            "maxHit" => self.max_hit = parse(element, text)?,
+            "hitpoints" => self.hitpoints = parse(element, text)?,
+            "attackSpeed" => self.attack_speed = parse(element, text)?,
+            "attackAnim" => self.attack_anim = parse(element, text)?,
+            "defenceAnim" => self.defence_anim = parse(element, text)?,
+            "deathAnim" => self.death_anim = parse(element, text)?,
+            "attackBonus" => self.attack_bonus = parse(element, text)?,
+            "defenceMelee" => self.defence_melee = parse(element, text)?,
+            "defenceRange" => self.defence_range = parse(element, text)?,
+            "defenceMage" => self.defence_mage = parse(element, text)?,
+            _ => bail!("unknown element <{element}>"),
+        }
+        Ok(())
+    }
+
+    /// the elements in file order, `None` values are left out like XStream does for null fields
+    fn elements(&self) -> [(&'static str, Option<String>); 20] {
+        [
+            ("id", Some(self.id.to_string())),
+            ("name", self.name.clone()),
+            ("examine", self.examine.clone()),
+            ("combat", Some(self.combat.to_string())),
+            ("size", Some(self.size.to_string())),
+            ("attackable", Some(self.attackable.to_string())),
+            ("aggressive", Some(self.aggressive.to_string())),
+            ("retreats", Some(self.retreats.to_string())),
+            ("poisonous", Some(self.poisonous.to_string())),
+            ("respawn", Some(self.respawn.to_string())),
+            ("maxHit", Some(self.max_hit.to_string())),
+            ("hitpoints", Some(self.hitpoints.to_string())),
+            ("attackSpeed", Some(self.attack_speed.to_string())),
+            ("attackAnim", Some(self.attack_anim.to_string())),
+            ("defenceAnim", Some(self.defence_anim.to_string())),
+            ("deathAnim", Some(self.death_anim.to_string())),
+            ("attackBonus", Some(self.attack_bonus.to_string())),
+            ("defenceMelee", Some(self.defence_melee.to_string())),
+            ("defenceRange", Some(self.defence_range.to_string())),
+            ("defenceMage", Some(self.defence_mage.to_string())),
+        ]
+    }
+}
+
+fn element_name(start: &BytesStart) -> String {
+    String::from_utf8_lossy(start.local_name().as_ref()).into_owned()
+}
+
+/// streams every definition out of the xml file without loading the whole document
+pub fn load_all(path: &Path) -> Result<Vec<NpcDefinition>> {
+    let mut reader = Reader::from_file(path)
+        .with_context(|| format!("could not read npc definitions from {}", path.display()))?;
+    parse(&mut reader).with_context(|| {
+        format!(
+            "could not parse npc definitions from {} at byte {}",
+            path.display(),
+            reader.buffer_position()
+        )
+    })
+}
+
+pub fn from_xml_str(xml: &str) -> Result<Vec<NpcDefinition>> {
+    let mut reader = Reader::from_str(xml);
+    parse(&mut reader).with_context(|| {
+        format!(
+            "could not parse npc definitions at byte {}",
+            reader.buffer_position()
+        )
+    })
+}
+
+fn parse<R: std::io::BufRead>(reader: &mut Reader<R>) -> Result<Vec<NpcDefinition>> {
+    let mut definitions = Vec::new();
+    let mut buf = Vec::new();
+    let mut definition: Option<NpcDefinition> = None;
+    let mut field: Option<(String, String)> = None;
+    // the elements of the current definition and the text since the last tag
+    let mut present: HashSet<String> = HashSet::new();
+    let mut text = String::new();
+
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(start) => {
+                let name = element_name(&start);
+                match (&mut definition, &field) {
+                    (None, _) if name == LIST_ELEMENT => text.clear(),
+                    (None, _) if name == DEFINITION_ELEMENT => {
+                        let mut started = NpcDefinition::default();
+                        started
+                            .layout
+                            .record_text(&name, DEFINITION_TEXT, &mut text);
+                        definition = Some(started);
+                        present.clear();
+                    }
+                    (Some(definition), None) => {
+                        definition.layout.record_text(&name, FIELD_TEXT, &mut text);
+                        present.insert(name.clone());
+                        field = Some((name, String::new()));
+                    }
+                    _ => bail!("unexpected element <{name}>"),
+                }
+            }
+            // an empty element such as `<name/>` sets the field to an empty string
+            Event::Empty(start) => match &mut definition {
+                Some(definition) if field.is_none() => {
+                    let name = element_name(&start);
+                    definition.layout.record_text(&name, FIELD_TEXT, &mut text);
+                    definition.set_field(&name, "")?;
+                    present.insert(name);
+                }
+                _ => bail!("unexpected element <{}/>", element_name(&start)),
+            },
+            Event::Text(event) => {
+                // text between the fields of a definition is ignored, like XStream does, but
+                // kept to write the definition back the same way
+                match &mut field {
+                    Some((_, value)) => value.push_str(&event.unescape()?),
+                    None => text.push_str(&event.unescape()?),
+                }
+            }
+            Event::CData(data) => {
+                if let Some((_, value)) = &mut field {
+                    value.push_str(&String::from_utf8_lossy(&data));
+                }
+            }
+            Event::End(end) => {
+                let name = String::from_utf8_lossy(end.local_name().as_ref()).into_owned();
+                if let Some((element, value)) = field.take() {
+                    let definition = definition
+                        .as_mut()
+                        .context("field outside of a definition")?;
+                    definition.set_field(&element, &value)?;
+                } else if name == DEFINITION_ELEMENT {
+                    if let Some(mut definition) = definition.take() {
+                        let layout = &mut definition.layout;
+                        layout.record_text(DEFINITION_END, DEFINITION_END_TEXT, &mut text);
+                        layout.missing = NpcDefinition::default()
+                            .elements()
+                            .into_iter()
+                            .filter(|(element, value)| {
+                                value.is_some() && !present.contains(*element)
+                            })
+                            .map(|(element, _)| element.to_string())
+                            .collect();
+                        definitions.push(definition);
+                    }
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    if definition.is_some() {
+        bail!("unterminated <{DEFINITION_ELEMENT}>");
+    }
+    Ok(definitions)
+}
+
+/// serializes the definitions in the same layout as the server's `npcDefinitions.xml`, writing
+/// back each definition's own layout
+pub fn to_xml_string(definitions: &[NpcDefinition]) -> String {
+    let initial = NpcDefinition::default().elements();
+    let mut xml = format!("<{LIST_ELEMENT}>");
+    for definition in definitions {
+        let layout = &definition.layout;
+        xml.push_str(layout.text(DEFINITION_ELEMENT, DEFINITION_TEXT));
+        xml.push_str(&format!("<{DEFINITION_ELEMENT}>"));
+        for ((element, value), (_, initial)) in definition.elements().into_iter().zip(&initial) {
+            let Some(value) = value else {
+                continue;
+            };
+            // a missing element is only added once its value has been changed
+            if layout.missing.iter().any(|missing| missing == element)
+                && Some(&value) == initial.as_ref()
+            {
+                continue;
+            }
+            xml.push_str(layout.text(element, FIELD_TEXT));
+            let value = partial_escape(&value);
+            xml.push_str(&format!("<{element}>{value}</{element}>"));
+        }
+        xml.push_str(layout.text(DEFINITION_END, DEFINITION_END_TEXT));
+        xml.push_str(&format!("</{DEFINITION_ELEMENT}>"));
+    }
+    // the file has no trailing newline
+    xml.push_str(&format!("\n</{LIST_ELEMENT}>"));
+    xml
+}
+
+pub fn save_all(path: &Path, definitions: &[NpcDefinition]) -> Result<()> {
+    std::fs::write(path, to_xml_string(definitions))
+        .with_context(|| format!("could not write npc definitions to {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// the second definition lacks <attackBonus>, has an over-indented element and a stray `>`
+    const DEFINITIONS: &str = "<list>
+ <npcDefinition>
+   <id>0</id>
+   <name>Hans</name>
+   <examine>Servant of the Duke of Lumbridge.</examine>
+   <combat>0</combat>
+   <size>1</size>
+   <attackable>false</attackable>
+   <aggressive>false</aggressive>
+   <retreats>false</retreats>
+   <poisonous>false</poisonous>
+   <respawn>10</respawn>
+   <maxHit>0</maxHit>
+   <hitpoints>0</hitpoints>
+   <attackSpeed>4000</attackSpeed>
+   <attackAnim>422</attackAnim>
+   <defenceAnim>404</defenceAnim>
+   <deathAnim>2304</deathAnim>
+   <attackBonus>20</attackBonus>
+   <defenceMelee>20</defenceMelee>
+   <defenceRange>20</defenceRange>
+   <defenceMage>20</defenceMage>
+  </npcDefinition>
+ <npcDefinition>
+   <id>978</id>
+   <name>Blessed giant rat</name>
+   <combat>9</combat>
+    <attackAnim>138</attackAnim>
+   <deathAnim>141</deathAnim>>
+   <defenceMelee>39</defenceMelee>
+  </npcDefinition>
+</list>";
+
+    #[test]
+    fn writes_definitions_back_unchanged() -> Result<()> {
+        let definitions = from_xml_str(DEFINITIONS)?;
+        assert_eq!(definitions.len(), 2);
+        assert_eq!(definitions[1].attack_bonus, 20);
+        assert_eq!(definitions[1].examine, None);
+        assert_eq!(to_xml_string(&definitions), DEFINITIONS);
+        Ok(())
+    }
+
+    #[test]
+    fn writes_missing_elements_once_changed() -> Result<()> {
+        let mut definitions = from_xml_str(DEFINITIONS)?;
+        definitions[1].attack_bonus = 30;
+        let added = DEFINITIONS.replace(
+            "<deathAnim>141</deathAnim>",
+            "<deathAnim>141</deathAnim>\n   <attackBonus>30</attackBonus>",
+        );
+        assert_eq!(to_xml_string(&definitions), added);
+        Ok(())
+    }
+}
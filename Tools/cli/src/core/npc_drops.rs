@@ -0,0 +1,78 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// mirrors `com.rs2.util.NpcDrop` as loaded from `data/cfg/npcdrops.json`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct NpcDrops {
+    pub id: i32,
+    pub items: Vec<ItemDrop>,
+}
+
+/// mirrors `com.rs2.game.npcs.drops.ItemDrop`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+pub struct ItemDrop {
+    pub item_id: i32,
+    /// the drop is rolled with `Misc.random(chance) == 0`, so 0 means always
+    pub chance: i32,
+    /// the inclusive minimum and maximum amount dropped
+    pub amounts: [i32; 2],
+}
+
+impl ItemDrop {
+    pub fn min_amount(&self) -> i32 {
+        self.amounts[0]
+    }
+
+    pub fn max_amount(&self) -> i32 {
+        self.amounts[1]
+    }
+
+    /// the probability of a single roll succeeding, `Misc.random(n)` returns `0..=n`
+    pub fn probability(&self) -> f64 {
+        1.0 / (f64::from(self.chance.max(0)) + 1.0)
+    }
+
+    /// a human readable rate such as `1/65`
+    pub fn rate(&self) -> String {
+        match self.chance {
+            c if c <= 0 => "always".to_string(),
+            c => format!("1/{}", i64::from(c) + 1),
+        }
+    }
+}
+
+pub fn load_all(path: &Path) -> Result<Vec<NpcDrops>> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("could not read npc drops from {}", path.display()))?;
+    serde_json::from_str(&contents)
+        .with_context(|| format!("could not parse npc drops from {}", path.display()))
+}
+
+/// finds the drop table of the given npc, the server falls back to a default table when there is none
+pub fn drops_for_npc(drops: &[NpcDrops], npc_id: i32) -> Option<&NpcDrops> {
+    drops.iter().find(|d| d.id == npc_id)
+}
+
+/// the server's fallback table for npcs without an entry in `npcdrops.json`
+/// see `NPCDropsHandler.getNpcDrops`
+pub fn default_drops() -> Vec<ItemDrop> {
+    vec![
+        ItemDrop {
+            item_id: 526,
+            chance: 0,
+            amounts: [1, 1],
+        },
+        ItemDrop {
+            item_id: 995,
+            chance: 3,
+            amounts: [1, 10],
+        },
+        ItemDrop {
+            item_id: 2677,
+            chance: 512,
+            amounts: [1, 1],
+        },
+    ]
+}
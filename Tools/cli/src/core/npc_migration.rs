@@ -0,0 +1,398 @@
+use std::{
+    collections::{BTreeMap, VecDeque},
+    path::{Path, PathBuf},
+};
+
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    json::to_gson_string,
+    npc_data::{self, NpcData},
+    npc_definition::{self, Layout, NpcDefinition},
+};
+
+/// a single npc as stored in `data/npc_definitions/{id}.json`, merged from
+/// `npcDefinitions.xml` and `npc.json`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MergedNpc {
+    /// the values from `npcDefinitions.xml`
+    #[serde(flatten)]
+    pub definition: NpcDefinition,
+    /// whether the npc is written to `npcDefinitions.xml`, npcs only in `npc.json` take their
+    /// definition from their first entry there
+    #[serde(default = "defined_by_default", skip_serializing_if = "is_defined")]
+    pub defined: bool,
+    /// the npc's entries in `npc.json`, which the server uses for names, combat levels and
+    /// hitpoints, taking the first entry of an id, npcs without entries are not listed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub listings: Vec<Listing>,
+}
+
+fn defined_by_default() -> bool {
+    true
+}
+
+fn is_defined(defined: &bool) -> bool {
+    *defined
+}
+
+/// an entry of the npc in `npc.json`, holding the values where it disagrees with the
+/// definition
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Listing {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub combat: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hitpoints: Option<i32>,
+}
+
+impl MergedNpc {
+    pub fn to_npc_data(&self, listing: &Listing) -> NpcData {
+        let definition = &self.definition;
+        NpcData {
+            hitpoints: listing.hitpoints.unwrap_or(definition.hitpoints),
+            name: listing
+                .name
+                .clone()
+                .unwrap_or_else(|| definition.name.clone().unwrap_or_default()),
+            combat: listing.combat.unwrap_or(definition.combat),
+            id: definition.id,
+            text_before: None,
+        }
+    }
+}
+
+/// how the legacy files were laid out, kept apart from the npcs so that regenerating gives
+/// back both files unchanged
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LegacyLayout {
+    /// the definitions in `npcDefinitions.xml` which differ from the usual layout, by id
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub definitions: BTreeMap<i32, Layout>,
+    /// the entries of `npc.json` in the order of the file, which is not ordered by id
+    #[serde(default)]
+    pub npc_list: Vec<ListEntry>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ListEntry {
+    pub id: i32,
+    /// the text before the entry where it is not the usual layout
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text_before: Option<String>,
+}
+
+/// a field which has a different value in `npcDefinitions.xml` than in `npc.json`
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct Conflict {
+    pub npc_id: i32,
+    pub field: &'static str,
+    pub definitions_value: String,
+    pub npc_list_value: String,
+}
+
+impl std::fmt::Display for Conflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "npc {}: {} is {:?} in npcDefinitions.xml but {:?} in npc.json",
+            self.npc_id, self.field, self.definitions_value, self.npc_list_value
+        )
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Merge {
+    /// the merged npcs ordered by id
+    pub npcs: Vec<MergedNpc>,
+    pub layout: LegacyLayout,
+    pub conflicts: Vec<Conflict>,
+    /// ids which are listed more than once in `npc.json`, the server uses the first entry
+    pub duplicate_list_ids: Vec<i32>,
+}
+
+/// merges both legacy sources into one definition per npc, keeping the values of both where
+/// they disagree so that `split` gives back both files unchanged
+pub fn merge(definitions: &[NpcDefinition], npc_list: &[NpcData]) -> Merge {
+    let mut layout = LegacyLayout::default();
+    let mut npcs: BTreeMap<i32, MergedNpc> = definitions
+        .iter()
+        .map(|definition| {
+            let mut definition = definition.clone();
+            let definition_layout = std::mem::take(&mut definition.layout);
+            if !definition_layout.is_usual() {
+                layout.definitions.insert(definition.id, definition_layout);
+            }
+            let npc = MergedNpc {
+                definition,
+                defined: true,
+                listings: Vec::new(),
+            };
+            (npc.definition.id, npc)
+        })
+        .collect();
+
+    let mut conflicts = Vec::new();
+    let mut duplicate_list_ids = Vec::new();
+    for listed in npc_list {
+        layout.npc_list.push(ListEntry {
+            id: listed.id,
+            text_before: listed.text_before.clone(),
+        });
+        let npc = npcs.entry(listed.id).or_insert_with(|| MergedNpc {
+            definition: NpcDefinition {
+                id: listed.id,
+                name: Some(listed.name.clone()),
+                combat: listed.combat,
+                hitpoints: listed.hitpoints,
+                ..NpcDefinition::default()
+            },
+            defined: false,
+            listings: Vec::new(),
+        });
+        if !npc.listings.is_empty() {
+            duplicate_list_ids.push(listed.id);
+        }
+
+        let definition = &npc.definition;
+        let mut conflict = |field, definitions_value: String, npc_list_value: String| {
+            if definitions_value == npc_list_value {
+                return false;
+            }
+            conflicts.push(Conflict {
+                npc_id: listed.id,
+                field,
+                definitions_value,
+                npc_list_value,
+            });
+            true
+        };
+        let name = definition.name.clone().unwrap_or_default();
+        let listing = Listing {
+            name: conflict("name", name, listed.name.clone()).then(|| listed.name.clone()),
+            combat: conflict(
+                "combat",
+                definition.combat.to_string(),
+                listed.combat.to_string(),
+            )
+            .then_some(listed.combat),
+            hitpoints: conflict(
+                "hitpoints",
+                definition.hitpoints.to_string(),
+                listed.hitpoints.to_string(),
+            )
+            .then_some(listed.hitpoints),
+        };
+        npc.listings.push(listing);
+    }
+
+    Merge {
+        npcs: npcs.into_values().collect(),
+        layout,
+        conflicts,
+        duplicate_list_ids,
+    }
+}
+
+/// splits the merged npcs back into the contents of `npcDefinitions.xml` and `npc.json`, the
+/// definitions keep the order of the npcs and the list entries the order of the layout,
+/// entries the layout does not have are appended ordered by id
+pub fn split(npcs: &[MergedNpc], layout: &LegacyLayout) -> (Vec<NpcDefinition>, Vec<NpcData>) {
+    let definitions = npcs
+        .iter()
+        .filter(|npc| npc.defined)
+        .map(|npc| {
+            let mut definition = npc.definition.clone();
+            if let Some(definition_layout) = layout.definitions.get(&definition.id) {
+                definition.layout = definition_layout.clone();
+            }
+            definition
+        })
+        .collect();
+
+    let mut unplaced: BTreeMap<i32, VecDeque<NpcData>> = npcs
+        .iter()
+        .map(|npc| {
+            let listed = npc.listings.iter().map(|listing| npc.to_npc_data(listing));
+            (npc.definition.id, listed.collect())
+        })
+        .collect();
+    let mut npc_list = Vec::new();
+    for entry in &layout.npc_list {
+        if let Some(mut listed) = unplaced.get_mut(&entry.id).and_then(VecDeque::pop_front) {
+            listed.text_before.clone_from(&entry.text_before);
+            npc_list.push(listed);
+        }
+    }
+    npc_list.extend(unplaced.into_values().flatten());
+    (definitions, npc_list)
+}
+
+/// checks that writing the merged npcs to JSON and regenerating the legacy files from them
+/// reproduces both files byte for byte
+pub fn check_round_trip(
+    npcs: &[MergedNpc],
+    layout: &LegacyLayout,
+    definitions_xml: &str,
+    npc_list_json: &str,
+) -> Result<()> {
+    let reloaded = npcs
+        .iter()
+        .map(|npc| Ok(serde_json::from_str(&to_json_string(npc)?)?))
+        .collect::<Result<Vec<MergedNpc>>>()?;
+    let reloaded_layout = serde_json::from_str(&to_gson_string(layout)?)?;
+    let (definitions, npc_list) = split(&reloaded, &reloaded_layout);
+    if npc_definition::to_xml_string(&definitions) != definitions_xml {
+        bail!("npcDefinitions.xml would not be regenerated unchanged");
+    }
+    if npc_data::to_json_string(&npc_list)? != npc_list_json {
+        bail!("npc.json would not be regenerated unchanged");
+    }
+    Ok(())
+}
+
+pub fn definition_path(dir: &Path, npc_id: i32) -> PathBuf {
+    dir.join(format!("{npc_id}.json"))
+}
+
+pub fn load(path: &Path) -> Result<MergedNpc> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("could not read npc definition {}", path.display()))?;
+    serde_json::from_str(&contents)
+        .with_context(|| format!("could not parse npc definition {}", path.display()))
+}
+
+/// loads every npc in the directory ordered by id
+pub fn load_all(dir: &Path) -> Result<Vec<MergedNpc>> {
+    let mut npcs = Vec::new();
+    for entry in std::fs::read_dir(dir)
+        .with_context(|| format!("could not read npc definitions from {}", dir.display()))?
+    {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            npcs.push(load(&path)?);
+        }
+    }
+    npcs.sort_by_key(|npc| npc.definition.id);
+    Ok(npcs)
+}
+
+/// serializes the npc in the same format as the item definitions
+pub fn to_json_string(npc: &MergedNpc) -> Result<String> {
+    to_gson_string(npc)
+}
+
+pub fn save(dir: &Path, npc: &MergedNpc) -> Result<()> {
+    let path = definition_path(dir, npc.definition.id);
+    std::fs::write(&path, to_json_string(npc)?)
+        .with_context(|| format!("could not write npc definition {}", path.display()))
+}
+
+/// loads the layout of the legacy files, the usual layout if the file does not exist
+pub fn load_layout(path: &Path) -> Result<LegacyLayout> {
+    if !path.exists() {
+        log::warn!("{} does not exist, using the usual layout", path.display());
+        return Ok(LegacyLayout::default());
+    }
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("could not read layout {}", path.display()))?;
+    serde_json::from_str(&contents)
+        .with_context(|| format!("could not parse layout {}", path.display()))
+}
+
+pub fn save_layout(path: &Path, layout: &LegacyLayout) -> Result<()> {
+    std::fs::write(path, to_gson_string(layout)?)
+        .with_context(|| format!("could not write layout {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use super::*;
+
+    const DEFINITIONS: &str = "<list>
+ <npcDefinition>
+   <id>1</id>
+   <name>Man</name>
+   <combat>2</combat>
+    <hitpoints>7</hitpoints>
+  </npcDefinition>
+ <npcDefinition>
+   <id>2</id>
+   <name>Woman</name>
+   <combat>2</combat>>
+   <hitpoints>7</hitpoints>
+  </npcDefinition>
+</list>";
+
+    const NPC_LIST: &str = r#"[
+  {
+    "hitpoints": 7,
+    "name": "Woman",
+    "combat": 2,
+    "id": 2
+  },
+
+  {
+    "hitpoints": 10,
+    "name": "Man",
+    "combat": 3,
+    "id": 1
+  },
+  {
+    "hitpoints": 7,
+    "name": "Guard",
+    "combat": 2,
+    "id": 2
+  }
+]
+"#;
+
+    #[test]
+    fn regenerates_both_files_unchanged() -> Result<()> {
+        let definitions = npc_definition::from_xml_str(DEFINITIONS)?;
+        let npc_list = npc_data::from_json_str(NPC_LIST)?;
+        let merge = merge(&definitions, &npc_list);
+
+        assert_eq!(merge.duplicate_list_ids, [2]);
+        let conflicts = merge
+            .conflicts
+            .iter()
+            .map(|conflict| (conflict.npc_id, conflict.field))
+            .collect_vec();
+        assert_eq!(conflicts, [(1, "combat"), (1, "hitpoints"), (2, "name")]);
+        check_round_trip(&merge.npcs, &merge.layout, DEFINITIONS, NPC_LIST)
+    }
+
+    #[test]
+    fn appends_new_npcs_to_the_npc_list() -> Result<()> {
+        let definitions = npc_definition::from_xml_str(DEFINITIONS)?;
+        let npc_list = npc_data::from_json_str(NPC_LIST)?;
+        let mut merge = merge(&definitions, &npc_list);
+
+        let mut guard: MergedNpc = serde_json::from_str(&to_json_string(&merge.npcs[0])?)?;
+        guard.definition.id = 3;
+        guard.definition.name = Some("Guard".to_string());
+        guard.listings = vec![Listing::default()];
+        let json = to_json_string(&guard)?;
+        assert!(!json.contains("defined"));
+        let guard: MergedNpc = serde_json::from_str(&json)?;
+        assert!(guard.defined);
+        merge.npcs.push(guard);
+
+        let (definitions, npc_list) = split(&merge.npcs, &merge.layout);
+        assert_eq!(definitions.len(), 3);
+        let ids = npc_list.iter().map(|npc| npc.id).collect_vec();
+        assert_eq!(ids, [2, 1, 2, 3]);
+        assert_eq!(npc_list[3].name, "Guard");
+        Ok(())
+    }
+}
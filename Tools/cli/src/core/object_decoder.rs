@@ -0,0 +1,136 @@
+use anyhow::{bail, Result};
+use serde::Serialize;
+
+use super::{archive::CONFIG_ARCHIVE, buffer::Buffer, cache::IndexedFileSystem};
+
+/// the number of right click options of an object
+pub const ACTION_SLOTS: usize = 10;
+
+/// the model type of objects which are drawn as a whole tile, see `ObjectType.INTERACTABLE`
+const INTERACTABLE_TYPE: u8 = 10;
+
+/// an object as stored in the cache, see `org.apollo.cache.def.ObjectDefinition`
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ObjectDefinition {
+    pub id: i32,
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub width: i32,
+    pub length: i32,
+    pub solid: bool,
+    pub impenetrable: bool,
+    pub interactive: bool,
+    pub obstructive: bool,
+    pub clipped: bool,
+    pub actions: [Option<String>; ACTION_SLOTS],
+}
+
+impl ObjectDefinition {
+    /// the actions which are set, in slot order
+    pub fn menu_actions(&self) -> impl Iterator<Item = &str> {
+        self.actions.iter().flatten().map(String::as_str)
+    }
+}
+
+/// decodes every object of the config archive's `loc.dat`, mirrors `ObjectDefinitionDecoder`
+pub fn decode_all(cache: &IndexedFileSystem) -> Result<Vec<ObjectDefinition>> {
+    cache
+        .archive(CONFIG_ARCHIVE)?
+        .decode_definitions("loc", decode)
+}
+
+/// decodes the opcodes of a single object until the terminating 0
+pub fn decode(id: i32, buffer: &mut Buffer) -> Result<ObjectDefinition> {
+    let mut object = ObjectDefinition {
+        id,
+        name: None,
+        description: None,
+        width: 1,
+        length: 1,
+        solid: true,
+        impenetrable: true,
+        interactive: false,
+        obstructive: false,
+        clipped: true,
+        actions: Default::default(),
+    };
+    let mut has_actions = false;
+    // only the first model opcode counts, like in the java decoder
+    let mut models: Option<Vec<Option<u8>>> = None;
+
+    loop {
+        let opcode = buffer.read_u8()?;
+        match opcode {
+            0 => {
+                // this overrides opcode 19, which the java decoder does as well
+                object.interactive = has_actions
+                    || models.as_ref().is_some_and(|types| {
+                        types[0].is_none_or(|model_type| model_type == INTERACTABLE_TYPE)
+                    });
+                return Ok(object);
+            }
+            // models with their types
+            1 => {
+                let amount = buffer.read_u8()?;
+                let mut types = Vec::with_capacity(amount.into());
+                for _ in 0..amount {
+                    skip(buffer, 2)?;
+                    types.push(Some(buffer.read_u8()?));
+                }
+                if models.is_none() && amount > 0 {
+                    models = Some(types);
+                }
+            }
+            2 => object.name = Some(buffer.read_string()?),
+            3 => object.description = Some(buffer.read_string()?),
+            // models without types
+            5 => {
+                let amount = buffer.read_u8()?;
+                skip(buffer, usize::from(amount) * 2)?;
+                if models.is_none() && amount > 0 {
+                    models = Some(vec![None; amount.into()]);
+                }
+            }
+            14 => object.width = i32::from(buffer.read_u8()?),
+            15 => object.length = i32::from(buffer.read_u8()?),
+            17 => object.solid = false,
+            18 => object.impenetrable = false,
+            19 => object.interactive = buffer.read_u8()? == 1,
+            // terrain adjustment, flat shading, walls and inversion
+            21..=23 | 62 | 74 => {}
+            // animation, map function and scene and area ids
+            24 | 60 | 65..=68 | 70..=72 => skip(buffer, 2)?,
+            // wall width, light ambience and diffusion, face flags and support items
+            28 | 29 | 39 | 69 | 75 => skip(buffer, 1)?,
+            30..=38 => {
+                has_actions = true;
+                let slot = usize::from(opcode - 30);
+                object.actions[slot] = Some(buffer.read_string()?);
+                object.interactive = true;
+            }
+            // recoloured colours
+            40 => {
+                let colours = buffer.read_u8()?;
+                skip(buffer, usize::from(colours) * 4)?;
+            }
+            64 => object.clipped = false,
+            73 => object.obstructive = true,
+            // morphisms, the count is unsigned like in the client
+            // the java decoder reads it signed and misreads objects with more than 127 morphisms
+            77 => {
+                skip(buffer, 4)?;
+                let count = buffer.read_u8()?;
+                skip(buffer, (usize::from(count) + 1) * 2)?;
+            }
+            _ => bail!(
+                "unknown opcode {opcode} at position {}",
+                buffer.position() - 1
+            ),
+        }
+    }
+}
+
+fn skip(buffer: &mut Buffer, len: usize) -> Result<()> {
+    buffer.read_bytes(len).map(|_| ())
+}
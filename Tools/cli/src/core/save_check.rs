@@ -0,0 +1,422 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+
+use super::{
+    characters::{
+        self, BANK_SIZE, CONTACT_SLOTS, EQUIPMENT_SLOTS, INVENTORY_SIZE, MAX_XP, QUEST_KEYS,
+        SKILL_COUNT,
+    },
+    item_definition::ItemDefinition,
+};
+
+/// the length of `Player.playerAppearance`
+pub const LOOK_PARTS: usize = 13;
+
+/// `Constants.HITPOINTS`
+const HITPOINTS: i64 = 3;
+
+/// the hitpoints level and experience `Player` gives new accounts, which does not match the
+/// experience table but is what every untrained save holds
+const NEW_HITPOINTS: (i64, i64) = (10, 1300);
+
+/// the highest level potions, brews and the dragon battleaxe can boost a skill of the level to,
+/// `Potions.getBoostedStat` and `Potions.doTheBrew` stay within a fifth of the level plus 1
+fn max_boosted_level(level: i64) -> i64 {
+    level + level / 5 + 2
+}
+
+/// the `[ACCOUNT]` and `[CHARACTER]` keys `PlayerSave.loadGame` reads with `Integer.parseInt`
+/// that rs-cli relies on
+const INT_KEYS: [&str; 4] = [
+    "character-rights",
+    "character-height",
+    "character-posx",
+    "character-posy",
+];
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum Rule {
+    MalformedLine,
+    MalformedProperty,
+    SlotOutOfRange,
+    DuplicateSlot,
+    UnknownItem,
+    NegativeAmount,
+    AmountOverflow,
+    XpOutOfRange,
+    LevelOutOfRange,
+}
+
+impl Rule {
+    pub fn id(self) -> &'static str {
+        match self {
+            Rule::MalformedLine => "malformed-line",
+            Rule::MalformedProperty => "malformed-property",
+            Rule::SlotOutOfRange => "slot-out-of-range",
+            Rule::DuplicateSlot => "duplicate-slot",
+            Rule::UnknownItem => "unknown-item",
+            Rule::NegativeAmount => "negative-amount",
+            Rule::AmountOverflow => "amount-overflow",
+            Rule::XpOutOfRange => "xp-out-of-range",
+            Rule::LevelOutOfRange => "level-out-of-range",
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct Problem {
+    pub rule: Rule,
+    pub file: PathBuf,
+    /// the line number, starting at 1
+    pub line: usize,
+    pub message: String,
+    /// what `--repair` does about the problem, `None` if it can not be repaired
+    pub repair: Option<Repair>,
+}
+
+impl std::fmt::Display for Problem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{}: [{}] {}",
+            self.file.display(),
+            self.line,
+            self.rule.id(),
+            self.message
+        )
+    }
+}
+
+/// what is done to the line of a problem to repair it
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case", tag = "action")]
+pub enum Repair {
+    /// the line is removed, which leaves its slot empty
+    Drop,
+    /// the value is set to the nearest valid value
+    Clamp { value: i64 },
+    /// the level is set to the level of the experience
+    RecomputeLevel { level: i64 },
+    /// the values are separated by tabs
+    Retab,
+}
+
+impl std::fmt::Display for Repair {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Repair::Drop => write!(f, "dropped the line"),
+            Repair::Clamp { value } => write!(f, "clamped to {value}"),
+            Repair::RecomputeLevel { level } => write!(f, "recomputed the level as {level}"),
+            Repair::Retab => write!(f, "separated the values by tabs"),
+        }
+    }
+}
+
+/// how a line is repaired
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Fix {
+    Drop,
+    Replace(String),
+}
+
+/// the problems of a save and the fixes for its repairable lines, keyed by line number
+#[derive(Clone, Debug, Default)]
+pub struct SaveCheck {
+    pub problems: Vec<Problem>,
+    pub fixes: BTreeMap<usize, Fix>,
+}
+
+impl SaveCheck {
+    /// applies every fix, keeping the other lines as written
+    pub fn repair(&self, contents: &str) -> String {
+        let mut repaired = String::with_capacity(contents.len());
+        for (index, line) in contents.lines().enumerate() {
+            match self.fixes.get(&(index + 1)) {
+                Some(Fix::Drop) => continue,
+                Some(Fix::Replace(replacement)) => repaired.push_str(replacement),
+                None => repaired.push_str(line),
+            }
+            repaired.push('\n');
+        }
+        repaired
+    }
+}
+
+/// a slot line being checked, problems and fixes are recorded against its line number
+struct LineCheck<'a> {
+    file: &'a Path,
+    line: usize,
+    problems: Vec<Problem>,
+    drop: bool,
+}
+
+impl LineCheck<'_> {
+    fn problem(&mut self, rule: Rule, message: String, repair: Option<Repair>) {
+        self.problems.push(Problem {
+            rule,
+            file: self.file.to_path_buf(),
+            line: self.line,
+            message,
+            repair,
+        });
+    }
+
+    /// records a problem which is repaired by dropping the line
+    fn drop(&mut self, rule: Rule, message: String) {
+        self.problem(rule, message, Some(Repair::Drop));
+        self.drop = true;
+    }
+
+    /// the problems of the line, every repair of a dropped line is the drop
+    fn finish(mut self) -> Vec<Problem> {
+        if self.drop {
+            for problem in &mut self.problems {
+                if problem.repair.is_some() {
+                    problem.repair = Some(Repair::Drop);
+                }
+            }
+        }
+        self.problems
+    }
+}
+
+/// checks every line `PlayerSave.loadGame` reads against the item definitions and the XP table
+pub fn check(file: &Path, contents: &str, items: &HashMap<i32, ItemDefinition>) -> SaveCheck {
+    let mut save_check = SaveCheck::default();
+    let mut section: Option<&str> = None;
+    // the line holding each slot of each section, the server keeps the last one
+    let mut slots: HashMap<(&str, i64), usize> = HashMap::new();
+
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            if name == "EOF" {
+                break;
+            }
+            section = Some(name);
+            continue;
+        }
+        let (Some(section), Some((key, value))) = (section, line.split_once('=')) else {
+            continue;
+        };
+        let (key, value) = (key.trim(), value.trim());
+        let mut check = LineCheck {
+            file,
+            line: index + 1,
+            problems: Vec::new(),
+            drop: false,
+        };
+
+        if matches!(section, "ACCOUNT" | "CHARACTER") {
+            if (INT_KEYS.contains(&key) || QUEST_KEYS.contains(&key))
+                && value.parse::<i32>().is_err()
+            {
+                check.problem(
+                    Rule::MalformedProperty,
+                    format!("{key} = {value} is not an integer"),
+                    None,
+                );
+            }
+            save_check.problems.extend(check.finish());
+            continue;
+        }
+
+        let Some((expected_key, fields, slot_limit)) = (match section {
+            "EQUIPMENT" => Some(("character-equip", 3, EQUIPMENT_SLOTS)),
+            "LOOK" => Some(("character-look", 2, LOOK_PARTS)),
+            "SKILLS" => Some(("character-skill", 3, SKILL_COUNT)),
+            "ITEMS" => Some(("character-item", 3, INVENTORY_SIZE)),
+            "BANK" => Some(("character-bank", 3, BANK_SIZE)),
+            "FRIENDS" => Some(("character-friend", 2, CONTACT_SLOTS)),
+            "IGNORES" => Some(("character-ignore", 2, CONTACT_SLOTS)),
+            _ => None,
+        }) else {
+            continue;
+        };
+        // the server skips lines with another key
+        if key != expected_key {
+            continue;
+        }
+
+        let Some(mut values) = parse_fields(&mut check, value, fields) else {
+            save_check.fixes.insert(check.line, Fix::Drop);
+            save_check.problems.extend(check.finish());
+            continue;
+        };
+        let mut rewritten = check.problems.iter().any(|p| p.rule == Rule::MalformedLine);
+
+        let slot = values[0];
+        if !(0..slot_limit as i64).contains(&slot) {
+            check.drop(
+                Rule::SlotOutOfRange,
+                format!("slot {slot} is not below {slot_limit}"),
+            );
+        }
+        // contact names are longs, every other value is read with Integer.parseInt
+        // the slot is checked above, amounts and experience are clamped below
+        let clamped = matches!(section, "EQUIPMENT" | "ITEMS" | "BANK" | "SKILLS");
+        let is_int = |position: usize| match section {
+            "FRIENDS" | "IGNORES" => false,
+            _ => position != 0 && !(clamped && position == 2),
+        };
+        for (position, value) in values.iter().enumerate() {
+            if is_int(position) && i32::try_from(*value).is_err() {
+                check.drop(
+                    Rule::MalformedLine,
+                    format!("value {value} does not fit in an integer"),
+                );
+            }
+        }
+
+        match section {
+            "EQUIPMENT" | "ITEMS" | "BANK" => {
+                let item_id = if section == "EQUIPMENT" {
+                    values[1]
+                } else {
+                    values[1] - 1
+                };
+                let amount = values[2];
+                if item_id < -1 {
+                    check.drop(Rule::UnknownItem, format!("item id {item_id} is invalid"));
+                } else if item_id >= 0 {
+                    let known = i32::try_from(item_id).is_ok_and(|id| items.contains_key(&id));
+                    if !known {
+                        check.drop(
+                            Rule::UnknownItem,
+                            format!("item {item_id} has no item definition"),
+                        );
+                    }
+                    if amount < 0 {
+                        check.drop(
+                            Rule::NegativeAmount,
+                            format!("item {item_id} has the negative amount {amount}"),
+                        );
+                    }
+                }
+                if amount > i64::from(i32::MAX) {
+                    check.problem(
+                        Rule::AmountOverflow,
+                        format!("amount {amount} is more than {}", i32::MAX),
+                        Some(Repair::Clamp {
+                            value: i64::from(i32::MAX),
+                        }),
+                    );
+                    values[2] = i64::from(i32::MAX);
+                    rewritten = true;
+                }
+            }
+            "SKILLS" => {
+                let xp = values[2];
+                if !(0..=i64::from(MAX_XP)).contains(&xp) {
+                    let clamped = xp.clamp(0, i64::from(MAX_XP));
+                    check.problem(
+                        Rule::XpOutOfRange,
+                        format!(
+                            "{} has {xp} experience, not within 0..={MAX_XP}",
+                            skill(slot)
+                        ),
+                        Some(Repair::Clamp { value: clamped }),
+                    );
+                    values[2] = clamped;
+                    rewritten = true;
+                }
+                // the saved level is the current one, which may be drained to 0, as prayer is,
+                // or boosted, so only levels no drain or boost reaches are problems
+                let level = i64::from(characters::level_for_xp(values[2] as i32));
+                let max_level = max_boosted_level(level);
+                let new_hitpoints = slot == HITPOINTS && (values[1], values[2]) == NEW_HITPOINTS;
+                if !(0..=max_level).contains(&values[1]) && !new_hitpoints {
+                    check.problem(
+                        Rule::LevelOutOfRange,
+                        format!(
+                            "{} is level {}, not within 0..={max_level} for its {} experience of level {level}",
+                            skill(slot),
+                            values[1],
+                            values[2]
+                        ),
+                        Some(Repair::RecomputeLevel { level }),
+                    );
+                    values[1] = level;
+                    rewritten = true;
+                }
+            }
+            _ => {}
+        }
+
+        if !check.drop {
+            if let Some(previous) = slots.insert((section, slot), check.line) {
+                // the overwritten line is dropped instead of repaired
+                for problem in &mut save_check.problems {
+                    if problem.line == previous && problem.repair.is_some() {
+                        problem.repair = Some(Repair::Drop);
+                    }
+                }
+                save_check.problems.push(Problem {
+                    rule: Rule::DuplicateSlot,
+                    file: file.to_path_buf(),
+                    line: previous,
+                    message: format!(
+                        "{expected_key} slot {slot} is overwritten by line {}",
+                        check.line
+                    ),
+                    repair: Some(Repair::Drop),
+                });
+                save_check.fixes.insert(previous, Fix::Drop);
+            }
+        }
+
+        if check.drop {
+            save_check.fixes.insert(check.line, Fix::Drop);
+        } else if rewritten {
+            let values = values.iter().map(i64::to_string).collect::<Vec<_>>();
+            save_check.fixes.insert(
+                check.line,
+                Fix::Replace(format!("{expected_key} = {}", values.join("\t"))),
+            );
+        }
+        save_check.problems.extend(check.finish());
+    }
+
+    save_check.problems.sort_by_key(|problem| problem.line);
+    save_check
+}
+
+/// parses the tab separated values the way `token2.split("\t+")` does, accepting values
+/// separated by other whitespace as a repairable problem
+fn parse_fields(check: &mut LineCheck, value: &str, required: usize) -> Option<Vec<i64>> {
+    let parse = |fields: Vec<&str>| -> Option<Vec<i64>> {
+        if fields.len() < required {
+            return None;
+        }
+        fields.iter().map(|field| field.parse().ok()).collect()
+    };
+    let tab_separated = value
+        .split('\t')
+        .filter(|field| !field.is_empty())
+        .collect();
+    if let Some(values) = parse(tab_separated) {
+        return Some(values);
+    }
+    if let Some(values) = parse(value.split_whitespace().collect()) {
+        check.problem(
+            Rule::MalformedLine,
+            "values are not separated by tabs".to_string(),
+            Some(Repair::Retab),
+        );
+        return Some(values);
+    }
+    check.drop(
+        Rule::MalformedLine,
+        format!("expected {required} integers separated by tabs, found {value:?}"),
+    );
+    None
+}
+
+fn skill(slot: i64) -> String {
+    usize::try_from(slot).map_or_else(|_| format!("skill {slot}"), characters::skill_name)
+}
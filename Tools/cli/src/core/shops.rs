@@ -0,0 +1,253 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use super::{item_definition::ItemDefinition, json::lenient_i32};
+
+/// `ShopHandler.MAX_SHOPS`
+pub const MAX_SHOPS: i32 = 800;
+/// `ShopHandler.MAX_SHOP_ITEMS`
+pub const MAX_SHOP_ITEMS: usize = 40;
+/// the shops `ShopAssistant` trades in tokkul
+pub const TOKKUL_SHOPS: [i32; 3] = [138, 139, 58];
+/// `ShopAssistant.RANGE_SHOP`, `PEST_SHOP` and `CASTLE_SHOP`, which trade in points
+pub const POINT_SHOPS: [i32; 3] = [111, 175, 112];
+
+/// mirrors `com.rs2.util.ShopData` as loaded from `data/cfg/shops.json`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Shop {
+    /// 0 for player owned shops, 1 for general stores which pay less, 2 otherwise
+    pub buy_modifier: i32,
+    /// 0 for player owned shops, 1 for general stores which buy anything,
+    /// 2 for shops which only buy what they stock
+    pub sell_modifier: i32,
+    pub name: String,
+    pub id: i32,
+    pub items: Vec<ShopItem>,
+}
+
+/// mirrors `com.rs2.util.ShopItems`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ShopItem {
+    #[serde(deserialize_with = "lenient_i32")]
+    pub item_id: i32,
+    #[serde(deserialize_with = "lenient_i32")]
+    pub item_amount: i32,
+}
+
+impl Shop {
+    pub fn is_player_owned(&self) -> bool {
+        self.buy_modifier == 0
+    }
+
+    /// whether the shop trades in coins rather than tokkul or points
+    pub fn uses_coins(&self) -> bool {
+        !TOKKUL_SHOPS.contains(&self.id) && !POINT_SHOPS.contains(&self.id)
+    }
+
+    /// the items the server actually loads, it stops at the first non-positive item id
+    pub fn stock(&self) -> impl Iterator<Item = &ShopItem> {
+        self.items.iter().take_while(|item| item.item_id > 0)
+    }
+
+    /// whether the shop will buy the given unnoted item from players
+    pub fn buys(&self, unnoted_id: i32) -> bool {
+        match self.sell_modifier {
+            1 => true,
+            2 => self.stock().any(|item| item.item_id == unnoted_id),
+            _ => false,
+        }
+    }
+
+    /// the price a player pays to buy the item, see `ShopAssistant.getItemShopValue`
+    pub fn buy_price(&self, item: Option<&ItemDefinition>) -> i32 {
+        shop_value(item, 1.0)
+    }
+
+    /// the price the shop pays a player for the unnoted item
+    pub fn sell_price(&self, item: Option<&ItemDefinition>) -> i32 {
+        // general stores pay less for items
+        let modifier = if self.buy_modifier == 1 { 0.90 } else { 1.0 };
+        shop_value(item, 0.85 * modifier)
+    }
+}
+
+fn shop_value(item: Option<&ItemDefinition>, ratio: f64) -> i32 {
+    let value = item.map_or(1.0, |item| f64::from(item.value) * ratio);
+    // minimum value of 1
+    value.floor().max(1.0) as i32
+}
+
+/// mirrors `ShopAssistant.getUnNoted`, which treats an item as noted if the previous id has the same name
+pub fn unnoted_id(items: &HashMap<i32, ItemDefinition>, item_id: i32) -> i32 {
+    let name = |id| {
+        items
+            .get(&id)
+            .and_then(|item: &ItemDefinition| item.name.as_deref())
+            .map(str::to_lowercase)
+    };
+    match (name(item_id), name(item_id - 1)) {
+        (Some(name), Some(previous)) if name == previous => item_id - 1,
+        _ => item_id,
+    }
+}
+
+pub fn load_all(path: &Path) -> Result<Vec<Shop>> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("could not read shops from {}", path.display()))?;
+    serde_json::from_str(&contents)
+        .with_context(|| format!("could not parse shops from {}", path.display()))
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ShopProblem {
+    /// more than one shop uses the same id, later entries overwrite earlier ones in the server
+    DuplicateShopId { shop_id: i32 },
+    /// the shop id does not fit in the server's shop arrays
+    ShopIdOutOfRange { shop_id: i32 },
+    /// the shop has more items than the server's shop arrays can hold
+    TooManyItems { shop_id: i32, count: usize },
+    /// items after a non-positive item id are never loaded by the server
+    UnreachableItems { shop_id: i32, count: usize },
+    /// the stocked item has no definition
+    UnknownItem { shop_id: i32, item_id: i32 },
+    /// the stocked item is a note
+    NotedItem { shop_id: i32, item_id: i32 },
+    /// the same item is stocked more than once
+    DuplicateItem { shop_id: i32, item_id: i32 },
+    /// the item can be bought from one shop and sold to another for more
+    Arbitrage {
+        item_id: i32,
+        buy_shop_id: i32,
+        buy_price: i32,
+        sell_shop_id: i32,
+        sell_price: i32,
+    },
+}
+
+impl std::fmt::Display for ShopProblem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShopProblem::DuplicateShopId { shop_id } => {
+                write!(f, "shop {shop_id}: shop id is used more than once")
+            }
+            ShopProblem::ShopIdOutOfRange { shop_id } => {
+                write!(f, "shop {shop_id}: shop id is not below {MAX_SHOPS}")
+            }
+            ShopProblem::TooManyItems { shop_id, count } => write!(
+                f,
+                "shop {shop_id}: {count} items exceed the limit of {MAX_SHOP_ITEMS}"
+            ),
+            ShopProblem::UnreachableItems { shop_id, count } => write!(
+                f,
+                "shop {shop_id}: {count} items after a non-positive item id are never loaded"
+            ),
+            ShopProblem::UnknownItem { shop_id, item_id } => {
+                write!(f, "shop {shop_id}: item {item_id} does not exist")
+            }
+            ShopProblem::NotedItem { shop_id, item_id } => {
+                write!(f, "shop {shop_id}: item {item_id} is noted")
+            }
+            ShopProblem::DuplicateItem { shop_id, item_id } => {
+                write!(f, "shop {shop_id}: item {item_id} is stocked more than once")
+            }
+            ShopProblem::Arbitrage {
+                item_id,
+                buy_shop_id,
+                buy_price,
+                sell_shop_id,
+                sell_price,
+            } => write!(
+                f,
+                "item {item_id}: bought from shop {buy_shop_id} for {buy_price} and sold to shop {sell_shop_id} for {sell_price}"
+            ),
+        }
+    }
+}
+
+/// checks every shop for problems which the server silently tolerates
+pub fn audit(shops: &[Shop], items: &HashMap<i32, ItemDefinition>) -> Vec<ShopProblem> {
+    let mut problems = Vec::new();
+
+    let mut seen_shops = HashSet::new();
+    for shop in shops {
+        if !seen_shops.insert(shop.id) {
+            problems.push(ShopProblem::DuplicateShopId { shop_id: shop.id });
+        }
+        if !(0..MAX_SHOPS).contains(&shop.id) {
+            problems.push(ShopProblem::ShopIdOutOfRange { shop_id: shop.id });
+        }
+        if shop.items.len() > MAX_SHOP_ITEMS {
+            problems.push(ShopProblem::TooManyItems {
+                shop_id: shop.id,
+                count: shop.items.len(),
+            });
+        }
+        let unreachable = shop.items.len() - shop.stock().count();
+        if unreachable > 0 {
+            problems.push(ShopProblem::UnreachableItems {
+                shop_id: shop.id,
+                count: unreachable,
+            });
+        }
+
+        let mut seen_items = HashSet::new();
+        for stocked in shop.stock() {
+            let item_id = stocked.item_id;
+            match items.get(&item_id) {
+                None => problems.push(ShopProblem::UnknownItem {
+                    shop_id: shop.id,
+                    item_id,
+                }),
+                Some(item) if item.is_note() => problems.push(ShopProblem::NotedItem {
+                    shop_id: shop.id,
+                    item_id,
+                }),
+                Some(_) => {}
+            }
+            if !seen_items.insert(item_id) {
+                problems.push(ShopProblem::DuplicateItem {
+                    shop_id: shop.id,
+                    item_id,
+                });
+            }
+        }
+    }
+
+    // player owned shops are priced by their owners, other currencies are not comparable to coins
+    let priced_shops = shops
+        .iter()
+        .filter(|shop| !shop.is_player_owned() && shop.uses_coins());
+    for buy_shop in priced_shops.clone() {
+        for stocked in buy_shop.stock() {
+            let buy_price = buy_shop.buy_price(items.get(&stocked.item_id));
+            let unnoted = unnoted_id(items, stocked.item_id);
+            let Some((sell_shop, sell_price)) = priced_shops
+                .clone()
+                .filter(|sell_shop| sell_shop.buys(unnoted))
+                .map(|sell_shop| (sell_shop, sell_shop.sell_price(items.get(&unnoted))))
+                .max_by_key(|(_, sell_price)| *sell_price)
+            else {
+                continue;
+            };
+            if sell_price > buy_price {
+                problems.push(ShopProblem::Arbitrage {
+                    item_id: stocked.item_id,
+                    buy_shop_id: buy_shop.id,
+                    buy_price,
+                    sell_shop_id: sell_shop.id,
+                    sell_price,
+                });
+            }
+        }
+    }
+
+    problems
+}
@@ -0,0 +1,97 @@
+use std::{collections::BTreeMap, path::Path};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// mirrors `com.rs2.util.NpcSpawn` as loaded from `data/cfg/spawns.json`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct NpcSpawn {
+    pub max_hit: i32,
+    pub strength: i32,
+    pub attack: i32,
+    pub x: i32,
+    pub y: i32,
+    pub id: i32,
+    /// 1 if the npc walks around its spawn point
+    pub walk: i32,
+    pub height: i32,
+}
+
+pub fn load_all(path: &Path) -> Result<Vec<NpcSpawn>> {
+    let contents = std::fs::read_to_string(path)
+        .with_context(|| format!("could not read spawns from {}", path.display()))?;
+    serde_json::from_str(&contents)
+        .with_context(|| format!("could not parse spawns from {}", path.display()))
+}
+
+impl NpcSpawn {
+    pub fn region_id(&self) -> i32 {
+        region_id(self.x, self.y)
+    }
+
+    /// the euclidean distance to the coordinate, matching `Misc.distance`
+    pub fn distance_to(&self, x: i32, y: i32) -> f64 {
+        f64::from(self.x - x).hypot(f64::from(self.y - y))
+    }
+}
+
+/// the id of the 64x64 region containing the coordinate, `(x >> 6) << 8 | (y >> 6)`
+pub fn region_id(x: i32, y: i32) -> i32 {
+    ((x >> 6) << 8) | (y >> 6)
+}
+
+/// the coordinate of the south west corner of the region
+pub fn region_base(region_id: i32) -> (i32, i32) {
+    ((region_id >> 8) << 6, (region_id & 0xFF) << 6)
+}
+
+/// an inclusive box of tiles
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Area {
+    pub min_x: i32,
+    pub min_y: i32,
+    pub max_x: i32,
+    pub max_y: i32,
+}
+
+impl Area {
+    pub fn new(x1: i32, y1: i32, x2: i32, y2: i32) -> Self {
+        Self {
+            min_x: x1.min(x2),
+            min_y: y1.min(y2),
+            max_x: x1.max(x2),
+            max_y: y1.max(y2),
+        }
+    }
+
+    pub fn contains(&self, x: i32, y: i32) -> bool {
+        (self.min_x..=self.max_x).contains(&x) && (self.min_y..=self.max_y).contains(&y)
+    }
+}
+
+impl std::str::FromStr for Area {
+    type Err = anyhow::Error;
+
+    /// parses `x1,y1,x2,y2`
+    fn from_str(s: &str) -> Result<Self> {
+        let coordinates = s
+            .split(',')
+            .map(|c| c.trim().parse::<i32>())
+            .collect::<Result<Vec<_>, _>>()
+            .with_context(|| format!("could not parse area {s}"))?;
+        match coordinates[..] {
+            [x1, y1, x2, y2] => Ok(Area::new(x1, y1, x2, y2)),
+            _ => anyhow::bail!("expected an area in the form x1,y1,x2,y2 but got {s}"),
+        }
+    }
+}
+
+/// counts the spawns per region
+pub fn region_density<'a>(spawns: impl IntoIterator<Item = &'a NpcSpawn>) -> BTreeMap<i32, usize> {
+    let mut density = BTreeMap::new();
+    for spawn in spawns {
+        *density.entry(spawn.region_id()).or_default() += 1;
+    }
+    density
+}
@@ -0,0 +1,566 @@
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+use itertools::Itertools;
+use serde::Serialize;
+use serde_json::json;
+
+use super::{
+    global_drops, item_definition, item_definition::ItemDefinition, item_stats, npc_data,
+    npc_definition, npc_drops, shops, spawns,
+};
+
+/// the locations of every server data file, relative to the server's `data` directory
+#[derive(Clone, Debug)]
+pub struct DataPaths {
+    pub item_definitions: PathBuf,
+    pub item_stats: PathBuf,
+    pub npc_definitions: PathBuf,
+    pub npc_list: PathBuf,
+    pub npc_drops: PathBuf,
+    pub shops: PathBuf,
+    pub spawns: PathBuf,
+    pub global_drops: PathBuf,
+}
+
+impl DataPaths {
+    pub fn new(data_dir: &Path) -> Self {
+        let cfg = data_dir.join("cfg");
+        Self {
+            item_definitions: data_dir.join("item_definitions"),
+            item_stats: cfg.join("ItemDefinitions.json"),
+            npc_definitions: cfg.join("npcDefinitions.xml"),
+            npc_list: cfg.join("npc.json"),
+            npc_drops: cfg.join("npcdrops.json"),
+            shops: cfg.join("shops.json"),
+            spawns: cfg.join("spawns.json"),
+            global_drops: cfg.join("globaldrops.json"),
+        }
+    }
+}
+
+/// known problems in the repository's data which are reported as warnings instead of errors,
+/// each is the rule, the file name and message of the issue, and why it is allowed
+const KNOWN_ISSUES: [(Rule, &str, &str, &str); 8] = [
+    (
+        Rule::DanglingItemId,
+        "npcdrops.json",
+        "npc 2881 drop 47: item 9431 does not exist",
+        "an item of a later revision, the server drops it with a null definition",
+    ),
+    (
+        Rule::DuplicateId,
+        "npc.json",
+        "npc 2558 is listed 2 times, the server uses the first",
+        "the second entry names Kree, which is not an npc of this revision",
+    ),
+    (
+        Rule::DuplicateId,
+        "shops.json",
+        "shop 329 is defined 2 times, the server uses the last",
+        SHOP_LOADED_TWICE,
+    ),
+    (
+        Rule::DuplicateId,
+        "shops.json",
+        "shop 330 is defined 2 times, the server uses the last",
+        SHOP_LOADED_TWICE,
+    ),
+    (
+        Rule::DuplicateId,
+        "shops.json",
+        "shop 331 is defined 2 times, the server uses the last",
+        SHOP_LOADED_TWICE,
+    ),
+    (
+        Rule::DuplicateId,
+        "shops.json",
+        "shop 332 is defined 2 times, the server uses the last",
+        SHOP_LOADED_TWICE,
+    ),
+    (
+        Rule::DuplicateId,
+        "shops.json",
+        "shop 335 is defined 2 times, the server uses the last",
+        SHOP_LOADED_TWICE,
+    ),
+    (
+        Rule::DuplicateId,
+        "shops.json",
+        "shop 336 is defined 2 times, the server uses the last",
+        SHOP_LOADED_TWICE,
+    ),
+];
+const SHOP_LOADED_TWICE: &str =
+    "`ShopHandler.loadShops` counts the stock of both copies, removing one changes how the shop restocks";
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum Rule {
+    ParseError,
+    MismatchedFileId,
+    DuplicateId,
+    DanglingItemId,
+    DanglingNpcId,
+    NoteLink,
+    MalformedAmount,
+}
+
+impl Rule {
+    pub const ALL: [Rule; 7] = [
+        Rule::ParseError,
+        Rule::MismatchedFileId,
+        Rule::DuplicateId,
+        Rule::DanglingItemId,
+        Rule::DanglingNpcId,
+        Rule::NoteLink,
+        Rule::MalformedAmount,
+    ];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            Rule::ParseError => "parse-error",
+            Rule::MismatchedFileId => "mismatched-file-id",
+            Rule::DuplicateId => "duplicate-id",
+            Rule::DanglingItemId => "dangling-item-id",
+            Rule::DanglingNpcId => "dangling-npc-id",
+            Rule::NoteLink => "note-link",
+            Rule::MalformedAmount => "malformed-amount",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Rule::ParseError => "the file could not be loaded into its typed model",
+            Rule::MismatchedFileId => "an item definition's id does not match its file name",
+            Rule::DuplicateId => "the same id is defined more than once",
+            Rule::DanglingItemId => "an item id is referenced which has no item definition",
+            Rule::DanglingNpcId => "an npc id is referenced which has no npc definition",
+            Rule::NoteLink => "noteGraphicId and noteInfoId do not link a note and its item",
+            Rule::MalformedAmount => "an amount or chance is out of range",
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq, Eq)]
+pub struct Issue {
+    pub rule: Rule,
+    pub severity: Severity,
+    pub file: PathBuf,
+    pub message: String,
+}
+
+impl std::fmt::Display for Issue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        write!(
+            f,
+            "{severity}[{0}] {1}: {2}",
+            self.rule.id(),
+            self.file.display(),
+            self.message
+        )
+    }
+}
+
+#[derive(Default)]
+struct Issues(Vec<Issue>);
+
+impl Issues {
+    fn push(&mut self, rule: Rule, severity: Severity, file: &Path, message: String) {
+        self.0.push(Issue {
+            rule,
+            severity,
+            file: file.to_path_buf(),
+            message,
+        });
+    }
+
+    /// records an error, or a warning if it is one of the known issues
+    fn error(&mut self, rule: Rule, file: &Path, message: String) {
+        let file_name = file.file_name().and_then(|name| name.to_str());
+        let known = KNOWN_ISSUES
+            .iter()
+            .find(|(known_rule, known_file, known_message, _)| {
+                *known_rule == rule && file_name == Some(known_file) && *known_message == message
+            });
+        match known {
+            Some((.., reason)) => {
+                let message = format!("{message}, a known issue: {reason}");
+                self.push(rule, Severity::Warning, file, message);
+            }
+            None => self.push(rule, Severity::Error, file, message),
+        }
+    }
+
+    fn warning(&mut self, rule: Rule, file: &Path, message: String) {
+        self.push(rule, Severity::Warning, file, message);
+    }
+
+    /// records a parse error and returns `None` if the file could not be loaded
+    fn load<T>(&mut self, file: &Path, result: anyhow::Result<T>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(e) => {
+                self.error(Rule::ParseError, file, format!("{e:#}"));
+                None
+            }
+        }
+    }
+}
+
+/// loads every data file and checks the references between them
+pub fn validate(paths: &DataPaths) -> Vec<Issue> {
+    let mut issues = Issues::default();
+
+    let items = validate_item_definitions(&paths.item_definitions, &mut issues);
+    let item_exists = |id: i32| items.contains_key(&id);
+
+    // the server creates npcs from their definitions, npc.json only names them
+    let npc_ids: Option<HashSet<i32>> = issues
+        .load(
+            &paths.npc_definitions,
+            npc_definition::load_all(&paths.npc_definitions),
+        )
+        .map(|definitions| definitions.iter().map(|npc| npc.id).collect());
+    // a file referencing npcs cannot pass without the definitions to check against
+    let npc_ids_for = |issues: &mut Issues, file: &Path| {
+        if npc_ids.is_none() {
+            issues.error(
+                Rule::DanglingNpcId,
+                file,
+                format!(
+                    "npc ids could not be checked as {} did not load",
+                    paths.npc_definitions.display()
+                ),
+            );
+        }
+        npc_ids.as_ref()
+    };
+
+    if let Some(npcs) = issues.load(&paths.npc_list, npc_data::load_all(&paths.npc_list)) {
+        let file = paths.npc_list.as_path();
+        for (id, count) in npcs.iter().counts_by(|npc| npc.id) {
+            if count > 1 {
+                issues.error(
+                    Rule::DuplicateId,
+                    file,
+                    format!("npc {id} is listed {count} times, the server uses the first"),
+                );
+            }
+        }
+    }
+
+    if let Some(drops) = issues.load(&paths.npc_drops, npc_drops::load_all(&paths.npc_drops)) {
+        let file = paths.npc_drops.as_path();
+        let npc_ids = npc_ids_for(&mut issues, file);
+        for (id, count) in drops.iter().counts_by(|npc| npc.id) {
+            if count > 1 {
+                issues.error(
+                    Rule::DuplicateId,
+                    file,
+                    format!("npc {id} has {count} drop tables"),
+                );
+            }
+        }
+        for npc in &drops {
+            // a table for an npc that is never listed is dead data rather than a bug
+            if npc_ids.is_some_and(|npc_ids| !npc_ids.contains(&npc.id)) {
+                issues.warning(
+                    Rule::DanglingNpcId,
+                    file,
+                    format!("npc {} has a drop table but no definition", npc.id),
+                );
+            }
+            for (index, drop) in npc.items.iter().enumerate() {
+                let location = format!("npc {} drop {index}", npc.id);
+                // slots with no item are empty, as in the shops
+                if drop.item_id > 0 && !item_exists(drop.item_id) {
+                    issues.error(
+                        Rule::DanglingItemId,
+                        file,
+                        format!("{location}: item {} does not exist", drop.item_id),
+                    );
+                }
+                if drop.min_amount() < 1 || drop.max_amount() < drop.min_amount() {
+                    issues.error(
+                        Rule::MalformedAmount,
+                        file,
+                        format!(
+                            "{location}: amounts {:?} are not a valid range",
+                            drop.amounts
+                        ),
+                    );
+                }
+                if drop.chance < 0 {
+                    issues.error(
+                        Rule::MalformedAmount,
+                        file,
+                        format!("{location}: chance {} is negative", drop.chance),
+                    );
+                }
+            }
+        }
+    }
+
+    if let Some(shops) = issues.load(&paths.shops, shops::load_all(&paths.shops)) {
+        let file = paths.shops.as_path();
+        for (id, count) in shops.iter().counts_by(|shop| shop.id) {
+            if count > 1 {
+                issues.error(
+                    Rule::DuplicateId,
+                    file,
+                    format!("shop {id} is defined {count} times, the server uses the last"),
+                );
+            }
+        }
+        for shop in &shops {
+            for (index, stocked) in shop.items.iter().enumerate() {
+                let location = format!("shop {} item {index}", shop.id);
+                if stocked.item_id > 0 && !item_exists(stocked.item_id) {
+                    issues.error(
+                        Rule::DanglingItemId,
+                        file,
+                        format!("{location}: item {} does not exist", stocked.item_id),
+                    );
+                }
+                if stocked.item_amount < 0 {
+                    issues.error(
+                        Rule::MalformedAmount,
+                        file,
+                        format!("{location}: amount {} is negative", stocked.item_amount),
+                    );
+                }
+            }
+        }
+    }
+
+    if let Some(spawns) = issues.load(&paths.spawns, spawns::load_all(&paths.spawns)) {
+        let file = paths.spawns.as_path();
+        let npc_ids = npc_ids_for(&mut issues, file);
+        for (index, spawn) in spawns.iter().enumerate() {
+            if npc_ids.is_some_and(|npc_ids| !npc_ids.contains(&spawn.id)) {
+                issues.error(
+                    Rule::DanglingNpcId,
+                    file,
+                    format!(
+                        "spawn {index} at ({}, {}, {}): npc {} has no definition",
+                        spawn.x, spawn.y, spawn.height, spawn.id
+                    ),
+                );
+            }
+        }
+    }
+
+    if let Some(drops) = issues.load(
+        &paths.global_drops,
+        global_drops::load_all(&paths.global_drops),
+    ) {
+        let file = paths.global_drops.as_path();
+        for (index, drop) in drops.iter().enumerate() {
+            let location = format!(
+                "global drop {index} at ({}, {}, {})",
+                drop.item_x,
+                drop.item_y,
+                drop.height()
+            );
+            if !item_exists(drop.id) {
+                issues.error(
+                    Rule::DanglingItemId,
+                    file,
+                    format!("{location}: item {} does not exist", drop.id),
+                );
+            }
+            if drop.amount < 1 {
+                issues.error(
+                    Rule::MalformedAmount,
+                    file,
+                    format!("{location}: amount {} is not positive", drop.amount),
+                );
+            }
+        }
+    }
+
+    if let Some(stats) = issues.load(&paths.item_stats, item_stats::load_all(&paths.item_stats)) {
+        let file = paths.item_stats.as_path();
+        for (id, count) in stats.iter().counts_by(|stats| stats.id) {
+            if count > 1 {
+                issues.error(
+                    Rule::DuplicateId,
+                    file,
+                    format!("item {id} has {count} entries"),
+                );
+            }
+            if !item_exists(id) {
+                issues.error(
+                    Rule::DanglingItemId,
+                    file,
+                    format!("item {id} does not exist"),
+                );
+            }
+        }
+    }
+
+    issues.0
+}
+
+fn validate_item_definitions(dir: &Path, issues: &mut Issues) -> HashMap<i32, ItemDefinition> {
+    let mut items: HashMap<i32, ItemDefinition> = HashMap::new();
+    let entries = match std::fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) => {
+            issues.error(
+                Rule::ParseError,
+                dir,
+                format!("could not read directory: {e}"),
+            );
+            return items;
+        }
+    };
+
+    let paths = entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .sorted()
+        .collect_vec();
+    for path in paths {
+        let Some(item) = issues.load(&path, item_definition::load(&path)) else {
+            continue;
+        };
+        let file_id = path
+            .file_stem()
+            .and_then(|stem| stem.to_str()?.parse::<i32>().ok());
+        if file_id != Some(item.id) {
+            issues.error(
+                Rule::MismatchedFileId,
+                &path,
+                format!("file contains item {}", item.id),
+            );
+        }
+        if items.contains_key(&item.id) {
+            issues.error(
+                Rule::DuplicateId,
+                &path,
+                format!("item {} is defined more than once", item.id),
+            );
+        }
+        items.insert(item.id, item);
+    }
+
+    let mut notes_by_item: HashMap<i32, Vec<i32>> = HashMap::new();
+    for item in items.values().sorted_by_key(|item| item.id) {
+        let path = item_definition::definition_path(dir, item.id);
+        if let Some(graphic_id) = item.note_graphic_id {
+            if !items.contains_key(&graphic_id) {
+                issues.error(
+                    Rule::DanglingItemId,
+                    &path,
+                    format!("noteGraphicId {graphic_id} does not exist"),
+                );
+            }
+            match item.note_info_id.map(|id| (id, items.get(&id))) {
+                None => issues.error(Rule::NoteLink, &path, "note has no noteInfoId".to_string()),
+                Some((id, None)) => issues.error(
+                    Rule::DanglingItemId,
+                    &path,
+                    format!("noteInfoId {id} does not exist"),
+                ),
+                Some((id, Some(target))) if target.is_note() => issues.error(
+                    Rule::NoteLink,
+                    &path,
+                    format!("noteInfoId {id} is itself a note"),
+                ),
+                Some((id, Some(_))) => notes_by_item.entry(id).or_default().push(item.id),
+            }
+        } else if let Some(note_id) = item.note_info_id {
+            // unnoted items usually leave noteInfoId unset, but when set it must point back
+            match items.get(&note_id) {
+                None => issues.error(
+                    Rule::DanglingItemId,
+                    &path,
+                    format!("noteInfoId {note_id} does not exist"),
+                ),
+                Some(note) if !note.is_note() || note.note_info_id != Some(item.id) => issues
+                    .error(
+                        Rule::NoteLink,
+                        &path,
+                        format!("noteInfoId {note_id} is not a note of this item"),
+                    ),
+                Some(_) => {}
+            }
+        }
+    }
+    for (item_id, notes) in notes_by_item.iter().sorted() {
+        if notes.len() > 1 {
+            let path = item_definition::definition_path(dir, *item_id);
+            issues.warning(
+                Rule::NoteLink,
+                &path,
+                format!("item is the noteInfoId of several notes: {notes:?}"),
+            );
+        }
+    }
+
+    items
+}
+
+/// converts the issues into a SARIF 2.1.0 log so they can be consumed by code scanning tools
+pub fn to_sarif(issues: &[Issue]) -> serde_json::Value {
+    let rules = Rule::ALL
+        .iter()
+        .map(|rule| {
+            json!({
+                "id": rule.id(),
+                "shortDescription": { "text": rule.description() },
+            })
+        })
+        .collect_vec();
+    let results = issues
+        .iter()
+        .map(|issue| {
+            // SARIF expects forward slashes and relative URIs without a leading `./`
+            let uri = issue.file.to_string_lossy().replace('\\', "/");
+            let uri = uri.trim_start_matches("./");
+            json!({
+                "ruleId": issue.rule.id(),
+                "level": match issue.severity {
+                    Severity::Warning => "warning",
+                    Severity::Error => "error",
+                },
+                "message": { "text": issue.message },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": { "uri": uri },
+                    },
+                }],
+            })
+        })
+        .collect_vec();
+
+    json!({
+        "version": "2.1.0",
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "rs-cli",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                },
+            },
+            "results": results,
+        }],
+    })
+}